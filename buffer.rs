@@ -0,0 +1,408 @@
+// Bytes do arquivo aberto: lidos para a memória ou deixados no disco, mapeados (--mmap) ou lidos aos poucos (--stream)
+use memmap2::Mmap;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::{Index, IndexMut, Range};
+use std::path::Path;
+
+const WINDOW: usize = 64 * 1024;  // Bytes lidos do disco de cada vez com --stream
+
+// Todos os valores de um byte, para `Index` devolver uma referência a um byte lido do disco
+static BYTE_VALUES: [u8; 256] = {
+    let mut values = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        values[i] = i as u8;
+        i += 1;
+    }
+    values
+};
+
+// Origem dos bytes de um arquivo que continua no disco
+pub trait Backend {
+    fn len(&self) -> usize;
+    // Bytes da faixa, sem as edições; quem lê em janelas busca no disco só o trecho pedido
+    fn read(&self, range: Range<usize>) -> Cow<'_, [u8]>;
+    fn byte(&self, offset: usize) -> u8;
+    // Abrir o arquivo de novo do mesmo jeito, depois de gravado
+    fn reopen(&self, path: &Path) -> io::Result<Box<dyn Backend>>;
+    // Se o arquivo pode ser copiado inteiro para a memória quando as edições mudarem o tamanho
+    fn resizable(&self) -> bool;
+    // Se alguma leitura falhou e devolveu zeros no lugar dos bytes, que não podem ser gravados de volta
+    fn read_failed(&self) -> bool {
+        false
+    }
+}
+
+impl Backend for Mmap {
+    fn len(&self) -> usize {
+        self[..].len()
+    }
+
+    fn read(&self, range: Range<usize>) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self[range])
+    }
+
+    fn byte(&self, offset: usize) -> u8 {
+        self[offset]
+    }
+
+    fn reopen(&self, path: &Path) -> io::Result<Box<dyn Backend>> {
+        Ok(Box::new(map(path)?))
+    }
+
+    fn resizable(&self) -> bool {
+        true
+    }
+}
+
+// Leitor que busca no disco só os trechos pedidos, guardando a última janela lida como cache
+pub struct FileWindow {
+    file: RefCell<File>,
+    len: usize,
+    cache: RefCell<(usize, Vec<u8>)>,  // Offset e bytes da última janela lida
+    failed: Cell<bool>,
+}
+
+impl FileWindow {
+    fn open(path: &Path) -> io::Result<FileWindow> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        Ok(FileWindow { file: RefCell::new(file), len, cache: RefCell::new((0, Vec::new())), failed: Cell::new(false) })
+    }
+
+    // Garantir que a janela em cache contém a faixa, que não pode ser maior que uma janela
+    fn load(&self, range: &Range<usize>) {
+        let mut cache = self.cache.borrow_mut();
+        let (start, bytes) = &*cache;
+        if range.start >= *start && range.end <= start + bytes.len() {
+            return;
+        }
+        let start = range.start - range.start % WINDOW;
+        let len = (range.end - start).max(WINDOW).min(self.len - start);
+        *cache = (start, self.read_at(start, len));
+    }
+
+    // Um erro de leitura (ex: o arquivo encolheu depois de aberto) deixa zeros no lugar dos bytes
+    // e marca o arquivo, para o salvamento recusar gravá-los
+    fn read_at(&self, offset: usize, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0; len];
+        let mut file = self.file.borrow_mut();
+        if file.seek(SeekFrom::Start(offset as u64)).and_then(|_| file.read_exact(&mut bytes)).is_err() {
+            self.failed.set(true);
+        }
+        bytes
+    }
+}
+
+impl Backend for FileWindow {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn read(&self, range: Range<usize>) -> Cow<'_, [u8]> {
+        // Faixas maiores que a janela (ex: o arquivo inteiro para um hash) são lidas sem passar pelo cache
+        if range.len() > WINDOW {
+            return Cow::Owned(self.read_at(range.start, range.len()));
+        }
+        self.load(&range);
+        let cache = self.cache.borrow();
+        Cow::Owned(cache.1[range.start - cache.0..range.end - cache.0].to_vec())
+    }
+
+    fn byte(&self, offset: usize) -> u8 {
+        self.load(&(offset..offset + 1));
+        let cache = self.cache.borrow();
+        cache.1[offset - cache.0]
+    }
+
+    fn reopen(&self, path: &Path) -> io::Result<Box<dyn Backend>> {
+        Ok(Box::new(FileWindow::open(path)?))
+    }
+
+    // Copiar um arquivo que só é lido aos poucos por ser grande demais esgotaria a memória
+    fn resizable(&self) -> bool {
+        false
+    }
+
+    fn read_failed(&self) -> bool {
+        self.failed.get()
+    }
+}
+
+fn map(path: &Path) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+    // SAFETY: o mapeamento só é válido enquanto nenhum outro processo alterar o arquivo
+    unsafe { Mmap::map(&file) }
+}
+
+pub enum Buffer {
+    Memory(Vec<u8>),
+    // Arquivo no disco; os bytes editados ficam no overlay até o salvamento
+    Disk { source: Box<dyn Backend>, overlay: HashMap<usize, u8> },
+}
+
+impl Buffer {
+    // Mapear o arquivo em vez de lê-lo inteiro, para abrir arquivos grandes rapidamente
+    pub fn map_file(path: &Path) -> io::Result<Buffer> {
+        Ok(Buffer::Disk { source: Box::new(map(path)?), overlay: HashMap::new() })
+    }
+
+    // Abrir o arquivo sem lê-lo: cada acesso busca no disco só a janela necessária
+    pub fn stream_file(path: &Path) -> io::Result<Buffer> {
+        Ok(Buffer::Disk { source: Box::new(FileWindow::open(path)?), overlay: HashMap::new() })
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Buffer::Memory(data) => data.len(),
+            Buffer::Disk { source, .. } => source.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Dados que continuam no disco, com as edições no overlay
+    pub fn is_on_disk(&self) -> bool {
+        matches!(self, Buffer::Disk { .. })
+    }
+
+    // Se inserções, remoções e mudanças de tamanho são possíveis (ver `vec_mut`)
+    pub fn can_resize(&self) -> bool {
+        match self {
+            Buffer::Memory(_) => true,
+            Buffer::Disk { source, .. } => source.resizable(),
+        }
+    }
+
+    pub fn get(&self, offset: usize) -> Option<u8> {
+        (offset < self.len()).then(|| self[offset])
+    }
+
+    // Faixa de bytes já com as edições aplicadas; só copia se a faixa tiver bytes editados ou vier de uma janela
+    pub fn slice(&self, range: Range<usize>) -> Cow<'_, [u8]> {
+        match self {
+            Buffer::Memory(data) => Cow::Borrowed(&data[range]),
+            Buffer::Disk { source, overlay } => {
+                let bytes = source.read(range.clone());
+                if !overlay.keys().any(|offset| range.contains(offset)) {
+                    return bytes;
+                }
+                let mut bytes = bytes.into_owned();
+                for (&offset, &value) in overlay.iter().filter(|(offset, _)| range.contains(offset)) {
+                    bytes[offset - range.start] = value;
+                }
+                Cow::Owned(bytes)
+            }
+        }
+    }
+
+    // Como `slice`, mas None se a faixa passar do fim
+    pub fn get_slice(&self, range: Range<usize>) -> Option<Cow<'_, [u8]>> {
+        (range.start <= range.end && range.end <= self.len()).then(|| self.slice(range))
+    }
+
+    // Conteúdo completo com as edições aplicadas
+    pub fn bytes(&self) -> Cow<'_, [u8]> {
+        self.slice(0..self.len())
+    }
+
+    // A faixa em pedaços de até uma janela, para percorrer um arquivo grande sem copiá-lo inteiro
+    pub fn chunks(&self, range: Range<usize>) -> impl Iterator<Item = Cow<'_, [u8]>> + '_ {
+        let end = range.end;
+        range.step_by(WINDOW).map(move |start| self.slice(start..(start + WINDOW).min(end)))
+    }
+
+    // Bytes do overlay diferentes do arquivo no disco; None para dados em memória, que não guardam o original
+    pub fn changed_count(&self) -> Option<usize> {
+        match self {
+            Buffer::Memory(_) => None,
+            Buffer::Disk { source, overlay } => Some(overlay.iter().filter(|(&offset, &value)| source.byte(offset) != value).count()),
+        }
+    }
+
+    // Bytes do arquivo no disco, sem as edições do overlay; None para dados em memória
+    pub fn disk_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            Buffer::Memory(_) => None,
+            Buffer::Disk { source, .. } => Some(source.read(0..source.len()).into_owned()),
+        }
+    }
+
+    // Acesso ao vetor para operações que mudam o tamanho; um arquivo no disco é copiado para a memória.
+    // Quem chama confere `can_resize` antes, para não copiar um arquivo lido aos poucos.
+    pub fn vec_mut(&mut self) -> &mut Vec<u8> {
+        if self.is_on_disk() {
+            *self = Buffer::Memory(self.bytes().into_owned());
+        }
+        match self {
+            Buffer::Memory(data) => data,
+            Buffer::Disk { .. } => unreachable!(),
+        }
+    }
+
+    // Escrever o conteúdo com as edições, uma janela por vez
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        for piece in self.chunks(0..self.len()) {
+            out.write_all(&piece)?;
+        }
+        match self {
+            Buffer::Disk { source, .. } if source.read_failed() => Err(read_error()),
+            _ => Ok(()),
+        }
+    }
+
+    // Gravar no caminho; o overlay é achatado e o arquivo aberto de novo do mesmo jeito após a gravação
+    pub fn save(&mut self, path: &Path) -> io::Result<()> {
+        let Buffer::Disk { source, .. } = self else {
+            return self.write_to(&mut File::create(path)?);
+        };
+        if source.read_failed() {
+            return Err(read_error());
+        }
+        // Escrever ao lado e só então trocar: gravar direto truncaria o próprio arquivo que está sendo lido
+        let temp = crate::sibling_path(path, ".tmp");
+        let result = File::create(&temp).and_then(|mut file| self.write_to(&mut file)).and_then(|()| {
+            if let Ok(meta) = fs::metadata(path) {
+                fs::set_permissions(&temp, meta.permissions())?;
+            }
+            fs::rename(&temp, path)
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&temp);
+            return result;
+        }
+        // Sem reabrir, continua valendo o arquivo antigo com o overlay, que tem o mesmo conteúdo
+        if let Buffer::Disk { source, overlay } = self {
+            if let Ok(reopened) = source.reopen(path) {
+                *source = reopened;
+                overlay.clear();
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_error() -> io::Error {
+    io::Error::other("falha ao ler o arquivo aberto; os bytes não lidos não foram gravados")
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Buffer::Memory(Vec::new())
+    }
+}
+
+impl From<Vec<u8>> for Buffer {
+    fn from(data: Vec<u8>) -> Self {
+        Buffer::Memory(data)
+    }
+}
+
+impl Index<usize> for Buffer {
+    type Output = u8;
+
+    fn index(&self, offset: usize) -> &u8 {
+        match self {
+            Buffer::Memory(data) => &data[offset],
+            Buffer::Disk { source, overlay } => overlay.get(&offset).unwrap_or(&BYTE_VALUES[source.byte(offset) as usize]),
+        }
+    }
+}
+
+impl IndexMut<usize> for Buffer {
+    fn index_mut(&mut self, offset: usize) -> &mut u8 {
+        match self {
+            Buffer::Memory(data) => &mut data[offset],
+            Buffer::Disk { source, overlay } => overlay.entry(offset).or_insert_with(|| source.byte(offset)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Arquivo temporário com bytes variados, maior que várias janelas
+    fn temp_file(name: &str, len: usize) -> (PathBuf, Vec<u8>) {
+        let path = std::env::temp_dir().join(format!("pokehex_buffer_{}_{}", std::process::id(), name));
+        let data: Vec<u8> = (0..len).map(|i| (i * 7 % 251) as u8).collect();
+        fs::write(&path, &data).unwrap();
+        (path, data)
+    }
+
+    #[test]
+    fn streamed_reads_match_the_file() {
+        let (path, data) = temp_file("leitura", 5 * WINDOW + 123);
+        let buffer = Buffer::stream_file(&path).unwrap();
+        assert_eq!(buffer.len(), data.len());
+        assert!(!buffer.can_resize());
+        for (start, end) in [(0, 16), (WINDOW - 5, WINDOW + 5), (3 * WINDOW, 4 * WINDOW + 10), (data.len() - 3, data.len()), (0, data.len())] {
+            assert_eq!(&*buffer.slice(start..end), &data[start..end]);
+        }
+        assert_eq!(buffer[2 * WINDOW + 1], data[2 * WINDOW + 1]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn streamed_edits_stay_in_the_overlay_until_saved() {
+        let (path, data) = temp_file("edicao", 2 * WINDOW);
+        let out = path.with_extension("out");
+        let mut buffer = Buffer::stream_file(&path).unwrap();
+        let value = data[WINDOW].wrapping_add(1);
+        buffer[WINDOW] = value;
+        assert_eq!(buffer[WINDOW], value);
+        assert_eq!(buffer.slice(WINDOW - 1..WINDOW + 2)[1], value);
+        assert_eq!(buffer.changed_count(), Some(1));
+        buffer.save(&out).unwrap();
+        assert!(buffer.is_on_disk() && !buffer.can_resize());
+        assert_eq!(buffer.changed_count(), Some(0));
+        assert_eq!(fs::read(&out).unwrap()[WINDOW], value);
+        fs::remove_file(path).unwrap();
+        fs::remove_file(out).unwrap();
+    }
+
+    #[test]
+    fn mapped_files_can_still_resize() {
+        let (path, data) = temp_file("mapa", 100);
+        let mut buffer = Buffer::map_file(&path).unwrap();
+        assert!(buffer.can_resize());
+        buffer.vec_mut().push(1);
+        assert!(!buffer.is_on_disk());
+        assert_eq!(&buffer.bytes()[..100], &data[..]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn streamed_saves_over_the_open_file_in_chunks() {
+        let (path, data) = temp_file("mesmo", 3 * WINDOW + 9);
+        let mut buffer = Buffer::stream_file(&path).unwrap();
+        buffer[2 * WINDOW] = !data[2 * WINDOW];
+        buffer.save(&path).unwrap();
+        let saved = fs::read(&path).unwrap();
+        assert_eq!(saved.len(), data.len());
+        assert_eq!(saved[2 * WINDOW], !data[2 * WINDOW]);
+        assert_eq!(&saved[..2 * WINDOW], &data[..2 * WINDOW]);
+        assert!(!crate::sibling_path(&path, ".tmp").exists());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn failed_reads_refuse_to_save() {
+        let (path, _) = temp_file("encolhido", 2 * WINDOW);
+        let out = path.with_extension("out");
+        let mut buffer = Buffer::stream_file(&path).unwrap();
+        fs::write(&path, [1, 2, 3]).unwrap();
+        assert_eq!(buffer[WINDOW + 1], 0);
+        assert!(buffer.save(&out).is_err());
+        assert!(buffer.write_to(&mut Vec::new()).is_err());
+        assert!(!out.exists());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,178 @@
+// CRC32 e MD5, os hashes usados para conferir ROMs (ex.: com o banco de dados do No-Intro)
+
+pub struct Checksums {
+    pub crc32: u32,
+    pub md5: [u8; 16],
+}
+
+impl Checksums {
+    pub fn md5_hex(&self) -> String {
+        self.md5.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+// Soma feita aos poucos, para um arquivo grande ser lido trecho por trecho em vez de inteiro
+pub struct Hasher {
+    crc: u32,
+    md5: [u32; 4],
+    pending: Vec<u8>,  // Bytes que ainda não completam um bloco de 64
+    len: u64,
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Hasher { crc: !0, md5: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476], pending: Vec::new(), len: 0 }
+    }
+}
+
+impl Hasher {
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.crc = crc32_update(self.crc, data);
+        self.len = self.len.wrapping_add(data.len() as u64);
+        if !self.pending.is_empty() {
+            let take = (64 - self.pending.len()).min(data.len());
+            self.pending.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.pending.len() < 64 {
+                return;
+            }
+            md5_block(&mut self.md5, &self.pending);
+            self.pending.clear();
+        }
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            md5_block(&mut self.md5, block);
+        }
+        self.pending.extend_from_slice(blocks.remainder());
+    }
+
+    pub fn finish(mut self) -> Checksums {
+        // Completar com 0x80, zeros e o tamanho em bits até um múltiplo de 64 bytes
+        let mut tail = std::mem::take(&mut self.pending);
+        tail.push(0x80);
+        while tail.len() % 64 != 56 {
+            tail.push(0);
+        }
+        tail.extend_from_slice(&self.len.wrapping_mul(8).to_le_bytes());
+        for block in tail.chunks_exact(64) {
+            md5_block(&mut self.md5, block);
+        }
+        let mut digest = [0u8; 16];
+        for (chunk, value) in digest.chunks_exact_mut(4).zip(self.md5) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        Checksums { crc32: !self.crc, md5: digest }
+    }
+}
+
+// Tabela do CRC32 (polinômio refletido 0xEDB88320), montada em tempo de compilação
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    data.iter().fold(crc, |crc, &byte| CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+// Deslocamentos de cada passo e constantes do MD5 (RFC 1321)
+const MD5_SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20,
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+const MD5_K: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+// Um bloco de 64 bytes do MD5 (RFC 1321)
+fn md5_block(state: &mut [u32; 4], block: &[u8]) {
+    let words: Vec<u32> = block.chunks_exact(4).map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]])).collect();
+    let [mut a, mut b, mut c, mut d] = *state;
+    for i in 0..64 {
+        let (f, g) = match i / 16 {
+            0 => ((b & c) | (!b & d), i),
+            1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+            2 => (b ^ c ^ d, (3 * i + 5) % 16),
+            _ => (c ^ (b | !d), (7 * i) % 16),
+        };
+        let rotated = a.wrapping_add(f).wrapping_add(MD5_K[i]).wrapping_add(words[g]).rotate_left(MD5_SHIFTS[i]);
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(rotated);
+    }
+    for (value, add) in state.iter_mut().zip([a, b, c, d]) {
+        *value = value.wrapping_add(add);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compute(data: &[u8]) -> Checksums {
+        let mut hasher = Hasher::default();
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    fn crc32(data: &[u8]) -> u32 {
+        compute(data).crc32
+    }
+
+    fn md5_hex(data: &[u8]) -> String {
+        compute(data).md5_hex()
+    }
+
+    #[test]
+    fn crc32_matches_the_reference_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn md5_matches_the_rfc_1321_values() {
+        assert_eq!(md5_hex(b""), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(md5_hex(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+        // 80 bytes: um bloco inteiro antes do bloco completado
+        assert_eq!(
+            md5_hex(b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"),
+            "57edf4a22be3c955ac49da2e2107b67a"
+        );
+    }
+
+    #[test]
+    fn hashing_in_pieces_matches_hashing_at_once() {
+        let data: Vec<u8> = (0..1000).map(|i| (i * 31 % 256) as u8).collect();
+        let whole = compute(&data);
+        for piece in [1, 7, 63, 64, 65, 200] {
+            let mut hasher = Hasher::default();
+            for chunk in data.chunks(piece) {
+                hasher.update(chunk);
+            }
+            let pieces = hasher.finish();
+            assert_eq!((pieces.crc32, pieces.md5), (whole.crc32, whole.md5));
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// Identificação dos jogos de Pokémon pelo cabeçalho do cartucho
+use crate::header::{Console, RomHeader};
+
+const SPECIES_NAMES: &str = "Nomes das espécies";
+const MOVE_NAMES: &str = "Nomes dos golpes";
+
+// Tabela de nomes indexada pelo número da espécie ou do golpe
+pub struct NameTable {
+    pub offset: usize,
+    pub entry_len: Option<usize>,  // Bytes de cada nome; None quando os nomes ficam em sequência, cada um até o terminador
+    pub first: u16,                // Índice do primeiro nome da tabela
+    pub count: u16,                // Quantidade de nomes
+    pub terminator: u8,            // Fim do nome (0x50 nos jogos de GB, 0xFF no GBA)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameId {
+    Red,
+    Green,  // Somente Japão
+    Blue,
+    Yellow,
+    Gold,
+    Silver,
+    Crystal,
+    Ruby,
+    Sapphire,
+    Emerald,
+    FireRed,
+    LeafGreen,
+}
+
+impl GameId {
+    pub fn name(self) -> &'static str {
+        match self {
+            GameId::Red => "Pokémon Red",
+            GameId::Green => "Pokémon Green",
+            GameId::Blue => "Pokémon Blue",
+            GameId::Yellow => "Pokémon Yellow",
+            GameId::Gold => "Pokémon Gold",
+            GameId::Silver => "Pokémon Silver",
+            GameId::Crystal => "Pokémon Crystal",
+            GameId::Ruby => "Pokémon Ruby",
+            GameId::Sapphire => "Pokémon Sapphire",
+            GameId::Emerald => "Pokémon Emerald",
+            GameId::FireRed => "Pokémon FireRed",
+            GameId::LeafGreen => "Pokémon LeafGreen",
+        }
+    }
+
+    // Estruturas conhecidas do jogo (versões americanas 1.0; outras revisões e regiões podem diferir)
+    pub fn known_offsets(self) -> Vec<(&'static str, usize)> {
+        match self {
+            GameId::Red | GameId::Blue => vec![
+                ("Dados dos golpes", 0x38000),
+                ("Atributos base", 0x383DE),
+                (SPECIES_NAMES, 0x1C21E),
+            ],
+            GameId::Gold => vec![
+                ("Dados dos golpes", 0x41AFE),
+                ("Atributos base", 0x51B0B),
+                (SPECIES_NAMES, 0x1B0B74),
+                (MOVE_NAMES, 0x1B1574),
+            ],
+            GameId::Crystal => vec![("Atributos base", 0x51424)],
+            GameId::Ruby => vec![
+                (SPECIES_NAMES, 0x1F716C),
+                (MOVE_NAMES, 0x1F8320),
+                ("Dados dos golpes", 0x1FB12C),
+                ("Atributos base", 0x1FEC18),
+                ("Itens", 0x3C5580),
+            ],
+            GameId::Sapphire => vec![
+                (SPECIES_NAMES, 0x1F70FC),
+                (MOVE_NAMES, 0x1F82B0),
+                ("Dados dos golpes", 0x1FB0BC),
+                ("Atributos base", 0x1FEBA8),
+            ],
+            GameId::Emerald => vec![
+                (SPECIES_NAMES, 0x3185C8),
+                (MOVE_NAMES, 0x31977C),
+                ("Dados dos golpes", 0x31C898),
+                ("Atributos base", 0x3203CC),
+                ("Itens", 0x5839A0),
+            ],
+            GameId::FireRed => vec![
+                (SPECIES_NAMES, 0x245EE0),
+                (MOVE_NAMES, 0x247094),
+                ("Dados dos golpes", 0x250C04),
+                ("Atributos base", 0x254784),
+                ("Itens", 0x3DB028),
+            ],
+            GameId::LeafGreen => vec![
+                (SPECIES_NAMES, 0x245EBC),
+                (MOVE_NAMES, 0x247070),
+                ("Dados dos golpes", 0x250BE0),
+                ("Atributos base", 0x254760),
+                ("Itens", 0x3DAE64),
+            ],
+            GameId::Green | GameId::Yellow | GameId::Silver => Vec::new(),
+        }
+    }
+
+    fn known_offset(self, label: &str) -> Option<usize> {
+        self.known_offsets().into_iter().find(|&(name, _)| name == label).map(|(_, offset)| offset)
+    }
+
+    // Nomes das espécies; na 1ª geração a ordem é a interna do jogo (1 = Rhydon), não a da Pokédex
+    pub fn species_names(self) -> Option<NameTable> {
+        let offset = self.known_offset(SPECIES_NAMES)?;
+        Some(match self {
+            GameId::Red | GameId::Green | GameId::Blue | GameId::Yellow => {
+                NameTable { offset, entry_len: Some(10), first: 1, count: 190, terminator: 0x50 }
+            }
+            GameId::Gold | GameId::Silver | GameId::Crystal => {
+                NameTable { offset, entry_len: Some(10), first: 1, count: 251, terminator: 0x50 }
+            }
+            _ => NameTable { offset, entry_len: Some(11), first: 0, count: 412, terminator: 0xFF },
+        })
+    }
+
+    // Nomes dos golpes; nos jogos de GB eles têm tamanhos diferentes e ficam um após o outro
+    pub fn move_names(self) -> Option<NameTable> {
+        let offset = self.known_offset(MOVE_NAMES)?;
+        Some(match self {
+            GameId::Red | GameId::Green | GameId::Blue | GameId::Yellow => {
+                NameTable { offset, entry_len: None, first: 1, count: 165, terminator: 0x50 }
+            }
+            GameId::Gold | GameId::Silver | GameId::Crystal => {
+                NameTable { offset, entry_len: None, first: 1, count: 251, terminator: 0x50 }
+            }
+            _ => NameTable { offset, entry_len: Some(13), first: 0, count: 355, terminator: 0xFF },
+        })
+    }
+}
+
+// Reconhecer o jogo pelo código (GBA) ou pelo título (GB); None para arquivos desconhecidos
+pub fn detect(header: &RomHeader) -> Option<GameId> {
+    match header.console {
+        Console::Gba => {
+            // O último caractere do código é a região (E, J, P, D, F, I, S)
+            match header.game_code.as_deref()?.get(..3)? {
+                "AXV" => Some(GameId::Ruby),
+                "AXP" => Some(GameId::Sapphire),
+                "BPE" => Some(GameId::Emerald),
+                "BPR" => Some(GameId::FireRed),
+                "BPG" => Some(GameId::LeafGreen),
+                _ => None,
+            }
+        }
+        Console::Gb => {
+            let title = header.title.as_str();
+            // Nos jogos de GBC o título divide espaço com o código do fabricante (ex.: "POKEMON_GLDAAUE")
+            match title {
+                "POKEMON RED" => Some(GameId::Red),
+                "POKEMON GREEN" => Some(GameId::Green),
+                "POKEMON BLUE" => Some(GameId::Blue),
+                "POKEMON YELLOW" => Some(GameId::Yellow),
+                _ if title.starts_with("POKEMON_GLD") => Some(GameId::Gold),
+                _ if title.starts_with("POKEMON_SLV") => Some(GameId::Silver),
+                _ if title.starts_with("PM_CRYSTAL") => Some(GameId::Crystal),
+                _ => None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(console: Console, title: &str, game_code: Option<&str>) -> RomHeader {
+        RomHeader {
+            console,
+            title: title.to_string(),
+            maker_code: "01".to_string(),
+            game_code: game_code.map(str::to_string),
+            cartridge_type: None,
+            rom_size: 0,
+        }
+    }
+
+    #[test]
+    fn gba_games_come_from_the_code_in_any_region() {
+        for (code, game) in [
+            ("AXVE", GameId::Ruby),
+            ("AXPJ", GameId::Sapphire),
+            ("BPEE", GameId::Emerald),
+            ("BPRD", GameId::FireRed),
+            ("BPGS", GameId::LeafGreen),
+        ] {
+            assert_eq!(detect(&header(Console::Gba, "", Some(code))), Some(game), "{}", code);
+        }
+        assert_eq!(detect(&header(Console::Gba, "POKEMON RED", Some("BZME"))), None);
+        assert_eq!(detect(&header(Console::Gba, "", Some("BP"))), None);
+        assert_eq!(detect(&header(Console::Gba, "", None)), None);
+    }
+
+    #[test]
+    fn gb_games_come_from_the_title() {
+        for (title, game) in [
+            ("POKEMON RED", GameId::Red),
+            ("POKEMON YELLOW", GameId::Yellow),
+            ("POKEMON_GLDAAUE", GameId::Gold),
+            ("POKEMON_SLVAAXE", GameId::Silver),
+            ("PM_CRYSTAL", GameId::Crystal),
+        ] {
+            assert_eq!(detect(&header(Console::Gb, title, None)), Some(game), "{}", title);
+        }
+        assert_eq!(detect(&header(Console::Gb, "TETRIS", None)), None);
+        assert_eq!(detect(&header(Console::Gb, "", Some("BPRE"))), None);
+    }
+}
@@ -0,0 +1,191 @@
+// Leitura do cabeçalho de cartuchos Game Boy / Game Boy Color e Game Boy Advance
+
+// Início do logotipo da Nintendo em 0x0104 nos cartuchos GB
+const GB_LOGO_START: [u8; 4] = [0xCE, 0xED, 0x66, 0x66];
+
+// Logotipo da Nintendo comprimido em 0x04-0x9F dos cartuchos GBA; o BIOS não inicia ROMs em que ele difere
+const GBA_LOGO: [u8; 156] = [
+    0x24, 0xFF, 0xAE, 0x51, 0x69, 0x9A, 0xA2, 0x21, 0x3D, 0x84, 0x82, 0x0A, 0x84, 0xE4, 0x09, 0xAD,
+    0x11, 0x24, 0x8B, 0x98, 0xC0, 0x81, 0x7F, 0x21, 0xA3, 0x52, 0xBE, 0x19, 0x93, 0x09, 0xCE, 0x20,
+    0x10, 0x46, 0x4A, 0x4A, 0xF8, 0x27, 0x31, 0xEC, 0x58, 0xC7, 0xE8, 0x33, 0x82, 0xE3, 0xCE, 0xBF,
+    0x85, 0xF4, 0xDF, 0x94, 0xCE, 0x4B, 0x09, 0xC1, 0x94, 0x56, 0x8A, 0xC0, 0x13, 0x72, 0xA7, 0xFC,
+    0x9F, 0x84, 0x4D, 0x73, 0xA3, 0xCA, 0x9A, 0x61, 0x58, 0x97, 0xA3, 0x27, 0xFC, 0x03, 0x98, 0x76,
+    0x23, 0x1D, 0xC7, 0x61, 0x03, 0x04, 0xAE, 0x56, 0xBF, 0x38, 0x84, 0x00, 0x40, 0xA7, 0x0E, 0xFD,
+    0xFF, 0x52, 0xFE, 0x03, 0x6F, 0x95, 0x30, 0xF1, 0x97, 0xFB, 0xC0, 0x85, 0x60, 0xD6, 0x80, 0x25,
+    0xA9, 0x63, 0xBE, 0x03, 0x01, 0x4E, 0x38, 0xE2, 0xF9, 0xA2, 0x34, 0xFF, 0xBB, 0x3E, 0x03, 0x44,
+    0x78, 0x00, 0x90, 0xCB, 0x88, 0x11, 0x3A, 0x94, 0x65, 0xC0, 0x7C, 0x63, 0x87, 0xF0, 0x3C, 0xAF,
+    0xD6, 0x25, 0xE4, 0x8B, 0x38, 0x0A, 0xAC, 0x72, 0x21, 0xD4, 0xF8, 0x07,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Console {
+    Gb,   // Game Boy / Game Boy Color
+    Gba,  // Game Boy Advance
+}
+
+pub struct RomHeader {
+    pub console: Console,
+    pub title: String,
+    pub maker_code: String,
+    pub game_code: Option<String>,   // Somente GBA (0xAC)
+    pub cartridge_type: Option<u8>,  // Somente GB (0x0147)
+    pub rom_size: usize,             // Em bytes
+}
+
+// Detectar o console pela extensão do arquivo ou, na falta dela, pelos bytes mágicos
+pub fn detect_console(data: &[u8], extension: Option<&str>) -> Option<Console> {
+    match extension.map(|ext| ext.to_ascii_lowercase()).as_deref() {
+        Some("gb") | Some("gbc") => return Some(Console::Gb),
+        Some("gba") => return Some(Console::Gba),
+        _ => {}
+    }
+
+    if data.get(0xB2) == Some(&0x96) {
+        // Valor fixo obrigatório do cabeçalho GBA
+        Some(Console::Gba)
+    } else if data.get(0x104..0x108) == Some(&GB_LOGO_START[..]) {
+        Some(Console::Gb)
+    } else {
+        None
+    }
+}
+
+// Ler o cabeçalho conforme o layout do console; `data` são os primeiros bytes do arquivo e `len` o tamanho total
+pub fn parse(data: &[u8], len: usize, console: Console) -> Option<RomHeader> {
+    match console {
+        Console::Gb => {
+            let title = ascii_field(data.get(0x134..=0x143)?);
+            let old_licensee = *data.get(0x14B)?;
+            // 0x33 indica que o código do fabricante está no campo novo (0x0144-0x0145)
+            let maker_code = if old_licensee == 0x33 {
+                ascii_field(data.get(0x144..=0x145)?)
+            } else {
+                format!("{:02X}", old_licensee)
+            };
+            // 0x0148 codifica 32 KB << n; valores fora do padrão caem no tamanho real do arquivo
+            let rom_size = match *data.get(0x148)? {
+                n @ 0..=8 => (32 * 1024) << n,
+                _ => len,
+            };
+            Some(RomHeader {
+                console,
+                title,
+                maker_code,
+                game_code: None,
+                cartridge_type: Some(*data.get(0x147)?),
+                rom_size,
+            })
+        }
+        Console::Gba => Some(RomHeader {
+            console,
+            title: ascii_field(data.get(0xA0..0xAC)?),
+            maker_code: ascii_field(data.get(0xB0..0xB2)?),
+            game_code: Some(ascii_field(data.get(0xAC..0xB0)?)),
+            cartridge_type: None,
+            // O cabeçalho GBA não guarda o tamanho da ROM
+            rom_size: len,
+        }),
+    }
+}
+
+// Problemas encontrados no cabeçalho GBA; vazio quando o cabeçalho parece válido
+pub fn check_gba(data: &[u8]) -> Vec<&'static str> {
+    let mut problems = Vec::new();
+    // O ponto de entrada em 0x00 é uma instrução ARM de desvio (B), com 0xEA no byte mais alto
+    if data.get(3) != Some(&0xEA) {
+        problems.push("ponto de entrada");
+    }
+    if data.get(0x04..0xA0) != Some(&GBA_LOGO[..]) {
+        problems.push("logotipo");
+    }
+    if data.get(0xB2) != Some(&0x96) {
+        problems.push("valor fixo em 0xB2");
+    }
+    // Complemento em 0xBD: -(soma de 0xA0..=0xBC) - 0x19
+    let complement = data
+        .get(0xA0..0xBD)
+        .map(|bytes| bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_sub(byte)).wrapping_sub(0x19));
+    if complement.is_none() || data.get(0xBD).copied() != complement {
+        problems.push("checksum em 0xBD");
+    }
+    problems
+}
+
+// Texto ASCII de um campo do cabeçalho, terminando no primeiro byte nulo ou não imprimível
+fn ascii_field(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take_while(|&&byte| (0x20..=0x7E).contains(&byte))
+        .map(|&byte| byte as char)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cabeçalho GBA mínimo: título, código do jogo, fabricante e o valor fixo
+    fn gba_header() -> Vec<u8> {
+        let mut data = vec![0; 0xC0];
+        data[0xA0..0xAC].copy_from_slice(b"POKEMON FIRE");
+        data[0xAC..0xB0].copy_from_slice(b"BPRE");
+        data[0xB0..0xB2].copy_from_slice(b"01");
+        data[0xB2] = 0x96;
+        data
+    }
+
+    #[test]
+    fn console_comes_from_the_extension_or_the_magic_bytes() {
+        let gba = gba_header();
+        let mut gb = vec![0; 0x150];
+        gb[0x104..0x108].copy_from_slice(&GB_LOGO_START);
+        assert_eq!(detect_console(&gba, None), Some(Console::Gba));
+        assert_eq!(detect_console(&gb, Some("bin")), Some(Console::Gb));
+        assert_eq!(detect_console(&gb, Some("GBA")), Some(Console::Gba));
+        assert_eq!(detect_console(&[0; 0x200], Some("GBC")), Some(Console::Gb));
+        assert_eq!(detect_console(&[0; 0x200], None), None);
+    }
+
+    #[test]
+    fn gba_fields_are_read_as_ascii() {
+        let header = parse(&gba_header(), 0x100_0000, Console::Gba).unwrap();
+        assert_eq!((header.title.as_str(), header.maker_code.as_str()), ("POKEMON FIRE", "01"));
+        assert_eq!(header.game_code.as_deref(), Some("BPRE"));
+        assert_eq!((header.cartridge_type, header.rom_size), (None, 0x100_0000));
+        assert!(parse(&[0; 0xB0], 0xB0, Console::Gba).is_none());
+    }
+
+    #[test]
+    fn gb_fields_follow_the_licensee_and_size_codes() {
+        let mut data = vec![0; 0x150];
+        data[0x134..0x13F].copy_from_slice(b"POKEMON RED");
+        data[0x147] = 0x13;
+        data[0x148] = 0x05;
+        data[0x14B] = 0x01;
+        let header = parse(&data, 0x10_0000, Console::Gb).unwrap();
+        assert_eq!((header.title.as_str(), header.maker_code.as_str()), ("POKEMON RED", "01"));
+        assert_eq!((header.cartridge_type, header.rom_size, header.game_code), (Some(0x13), 1024 * 1024, None));
+        // 0x33 manda ler o código novo; um código de tamanho fora do padrão usa o tamanho do arquivo
+        data[0x14B] = 0x33;
+        data[0x144..0x146].copy_from_slice(b"01");
+        data[0x148] = 0x52;
+        let header = parse(&data, 0x12_0000, Console::Gb).unwrap();
+        assert_eq!((header.maker_code.as_str(), header.rom_size), ("01", 0x12_0000));
+        assert!(parse(&data[..0x14B], 0x14B, Console::Gb).is_none());
+    }
+
+    #[test]
+    fn gba_check_names_each_bad_field() {
+        let mut data = gba_header();
+        assert_eq!(check_gba(&data), vec!["ponto de entrada", "logotipo", "checksum em 0xBD"]);
+        data[3] = 0xEA;
+        data[0x04..0xA0].copy_from_slice(&GBA_LOGO);
+        data[0xBD] = data[0xA0..0xBD].iter().fold(0u8, |sum, &byte| sum.wrapping_sub(byte)).wrapping_sub(0x19);
+        assert!(check_gba(&data).is_empty());
+        data[0xB2] = 0;
+        assert_eq!(check_gba(&data), vec!["valor fixo em 0xB2", "checksum em 0xBD"]);
+        assert_eq!(check_gba(&data[..0xBD]).last(), Some(&"checksum em 0xBD"));
+    }
+}
@@ -0,0 +1,160 @@
+// Lista dos atalhos exibida pela ajuda (?); ao criar ou mudar um atalho no loop principal, atualize aqui
+use crate::keymap::{Action, KeyMap};
+use self::Shortcut::{Bound, Keys, Pair};
+
+// Teclas fixas ou a tecla configurada para uma ação
+pub enum Shortcut {
+    Keys(&'static str),
+    Bound(Action),
+    // Modificador com as teclas de duas ações de movimento (ex: Ctrl+Left/Right)
+    Pair(&'static str, Action, Action),
+}
+
+pub const BINDINGS: &[(&str, &[(Shortcut, &str)])] = &[
+    ("Navegação", &[
+        (Bound(Action::Up), "subir"),
+        (Bound(Action::Down), "descer"),
+        (Bound(Action::Left), "voltar um byte"),
+        (Bound(Action::Right), "avançar um byte"),
+        (Pair("Ctrl+", Action::Left, Action::Right), "avançar um passo"),
+        (Keys("Ctrl+D"), "definir o passo"),
+        (Keys("Ctrl+Q"), "tamanho do registro"),
+        (Pair("Ctrl+", Action::Up, Action::Down), "registro anterior/próximo"),
+        (Bound(Action::PageUp), "página acima"),
+        (Bound(Action::PageDown), "página abaixo"),
+        (Bound(Action::Start), "início do arquivo"),
+        (Bound(Action::End), "fim do arquivo"),
+        (Bound(Action::Goto), "ir para offset ou %"),
+        (Keys("H / Shift+H"), "base relativa/absoluta"),
+        (Keys("M / J"), "marcar/próximo marcador"),
+        (Keys("Ctrl+Shift+1-9"), "ir ao marcador N"),
+        (Keys("Ctrl+J"), "estruturas conhecidas"),
+        (Keys("Shift+J / Shift+K"), "próximo/anterior terminador"),
+        (Keys("Ctrl+Shift+J"), "bytes terminadores"),
+        (Keys("P / Backspace"), "seguir/voltar ponteiro"),
+        (Keys("Ctrl+[ / Ctrl+]"), "voltar/avançar no histórico"),
+        (Keys("Tab"), "painel hex/ASCII"),
+        (Keys("Ctrl+Tab, Ctrl+1-9"), "trocar/escolher aba"),
+    ]),
+    ("Edição", &[
+        (Keys("0-9, A-F"), "digitar sobre o cursor"),
+        (Bound(Action::EditByte), "editar o byte"),
+        (Keys("+ / -"), "somar/subtrair 1"),
+        (Keys("Shift+0-7"), "inverter um bit"),
+        (Keys("Insert"), "modo inserir/sobrescrever"),
+        (Keys("Shift+Insert / Delete"), "inserir/remover byte"),
+        (Keys("Z"), "byte de preenchimento"),
+        (Keys("Shift+movimento, [ / ]"), "selecionar"),
+        (Keys("Esc"), "limpar seleção"),
+        (Keys("Ctrl+F"), "preencher a seleção"),
+        (Keys("Ctrl+Shift+L"), "bloquear/desbloquear seleção"),
+        (Keys("Ctrl+B"), "AND/OR/XOR/NOT"),
+        (Keys("Ctrl+C / Ctrl+V"), "copiar/colar"),
+        (Keys("Ctrl+Shift+C / V"), "copiar/colar hex do sistema"),
+        (Bound(Action::Undo), "desfazer"),
+        (Bound(Action::Redo), "refazer"),
+        (Keys("W"), "escrever ponteiro"),
+        (Keys("Shift+W"), "editar inteiro em decimal"),
+        (Keys("Shift+N"), "editar texto pela tabela"),
+        (Keys("Ctrl+Shift+E"), "editar campo do modelo"),
+        (Keys("Ctrl+M"), "corrigir tamanho"),
+        (Keys("K"), "corrigir checksums GB"),
+    ]),
+    ("Arquivo", &[
+        (Bound(Action::Open), "abrir"),
+        (Bound(Action::Save), "salvar"),
+        (Bound(Action::SaveAs), "salvar como"),
+        (Keys("R"), "recarregar"),
+        (Keys("Shift+S / Shift+R"), "guardar/restaurar instantâneo"),
+        (Keys("Ctrl+L"), "arquivos recentes"),
+        (Keys("Ctrl+W"), "fechar aba"),
+        (Keys("Ctrl+R"), "somente leitura"),
+        (Keys("Ctrl+O / Ctrl+N"), "referência/próxima diferença"),
+        (Keys("L"), "carregar tabela .tbl"),
+        (Keys("Shift+L"), "descompactar/recompactar LZ77"),
+        (Keys("X / Ctrl+E"), "exportar dump/array C"),
+        (Keys("Shift+X / Shift+I"), "exportar/importar bytes .bin"),
+        (Keys("Ctrl+P / Ctrl+G"), "aplicar/gerar IPS"),
+        (Keys("Ctrl+Shift+P"), "aplicar roteiro de edição"),
+        (Bound(Action::Quit), "sair"),
+    ]),
+    ("Busca", &[
+        (Bound(Action::Search), "buscar bytes (?? = qualquer)"),
+        (Bound(Action::SearchText), "buscar texto"),
+        (Keys("Ctrl+T"), "maiúsculas na busca"),
+        (Bound(Action::FindNext), "próxima ocorrência"),
+        (Keys("Ctrl+H"), "substituir"),
+        (Keys("Ctrl+U"), "contar ocorrências"),
+        (Keys("U"), "espaço livre"),
+    ]),
+    ("Exibição", &[
+        (Bound(Action::Help), "esta ajuda"),
+        (Keys(", / ."), "bytes por linha"),
+        (Keys("V"), "visão dividida (Tab troca)"),
+        (Keys("Shift+T"), "tiles 4bpp na janela"),
+        (Keys("< / >"), "tiles por linha"),
+        (Keys("Shift+P"), "paleta dos tiles no cursor"),
+        (Keys("Shift+V"), "cores BGR555 na janela"),
+        (Keys("Shift+M"), "minimapa da entropia"),
+        (Keys("Ctrl+Shift+M"), "modelo de estrutura"),
+        (Keys("Ctrl+A"), "modo do painel ASCII"),
+        (Keys("Ctrl+X"), "endereços hex/decimal"),
+        (Keys("Ctrl+I"), "little/big-endian"),
+        (Keys("I"), "cabeçalho do cartucho"),
+        (Keys("Y"), "CRC32/MD5"),
+        (Keys("Shift+Q"), "espécie/golpe do valor no cursor"),
+        (Keys("Ctrl+K"), "limpar destaque das edições"),
+    ]),
+];
+
+// Linhas da ajuda em duas colunas, com as categorias distribuídas para equilibrar a altura
+pub fn lines(keymap: &KeyMap) -> Vec<String> {
+    let mut columns: [Vec<String>; 2] = [Vec::new(), Vec::new()];
+    // Título, atalhos e uma linha em branco por categoria
+    let total: usize = BINDINGS.iter().map(|(_, keys)| keys.len() + 2).sum();
+    for (category, keys) in BINDINGS {
+        // A categoria fica na esquerda enquanto ao menos metade dela couber na metade da altura total
+        let height = keys.len() + 2;
+        let column = if columns[1].is_empty() && columns[0].len() + height / 2 <= total / 2 { 0 } else { 1 };
+        columns[column].push(format!("== {} ==", category));
+        for (shortcut, action) in keys.iter() {
+            let key = match shortcut {
+                Keys(keys) => keys.to_string(),
+                // O * marca as teclas que podem ser trocadas em ~/.pokehex_keys
+                Bound(bound) => format!("{}*", keymap.get(*bound)),
+                Pair(modifier, first, second) => {
+                    format!("{}{}/{}*", modifier, keymap.get(*first).key_name(), keymap.get(*second).key_name())
+                }
+            };
+            columns[column].push(format!("{:<22} {}", key, action));
+        }
+        columns[column].push(String::new());
+    }
+
+    let width = columns[0].iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let rows = columns[0].len().max(columns[1].len());
+    let mut lines = vec!["Atalhos (qualquer tecla fecha; * = configurável em ~/.pokehex_keys)".to_string(), String::new()];
+    for row in 0..rows {
+        let left = columns[0].get(row).map_or("", String::as_str);
+        let right = columns[1].get(row).map_or("", String::as_str);
+        lines.push(format!("{:<width$}   {}", left, right, width = width));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap;
+
+    #[test]
+    fn remapped_keys_show_in_the_help() {
+        let (keymap, warnings) = keymap::parse("mover_esquerda=H\nmover_direita=L\nmover_cima=K\nmover_baixo=J\nsalvar=F5");
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let lines = lines(&keymap);
+        for expected in ["Ctrl+H/L*", "Ctrl+K/J*", "F5*"] {
+            assert!(lines.iter().any(|line| line.contains(expected)), "{}", expected);
+        }
+        assert!(!lines.iter().any(|line| line.contains("Ctrl+Left/Right")));
+    }
+}
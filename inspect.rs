@@ -0,0 +1,121 @@
+// Inspetor de dados: interpretações numéricas dos bytes a partir de um offset
+
+// Ordem dos bytes usada nos valores de mais de um byte
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Endian {
+    Little,  // GB/GBA
+    Big,
+}
+
+impl Endian {
+    pub fn toggled(self) -> Endian {
+        match self {
+            Endian::Little => Endian::Big,
+            Endian::Big => Endian::Little,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Endian::Little => "little-endian",
+            Endian::Big => "big-endian",
+        }
+    }
+}
+
+// Cada valor é None quando não há bytes suficientes até o fim do arquivo.
+pub struct InspectResult {
+    pub offset: usize,
+    pub endian: Endian,
+    pub u8: Option<u8>,
+    pub i8: Option<i8>,
+    pub u16: Option<u16>,
+    pub i16: Option<i16>,
+    pub u32: Option<u32>,
+    pub i32: Option<i32>,
+    pub f32: Option<f32>,
+}
+
+// `bytes` são os bytes a partir de `offset` (bastam 4)
+pub fn inspect(bytes: &[u8], offset: usize, endian: Endian) -> InspectResult {
+    let bytes2 = bytes.get(..2).map(|b| [b[0], b[1]]);
+    let bytes4 = bytes.get(..4).map(|b| [b[0], b[1], b[2], b[3]]);
+    let u16 = bytes2.map(|b| match endian {
+        Endian::Little => u16::from_le_bytes(b),
+        Endian::Big => u16::from_be_bytes(b),
+    });
+    let u32 = bytes4.map(|b| match endian {
+        Endian::Little => u32::from_le_bytes(b),
+        Endian::Big => u32::from_be_bytes(b),
+    });
+
+    InspectResult {
+        offset,
+        endian,
+        u8: bytes.first().copied(),
+        i8: bytes.first().map(|&b| b as i8),
+        u16,
+        i16: u16.map(|v| v as i16),
+        u32,
+        i32: u32.map(|v| v as i32),
+        f32: u32.map(f32::from_bits),
+    }
+}
+
+impl InspectResult {
+    // Linhas de texto do painel do inspetor
+    pub fn lines(&self) -> Vec<String> {
+        fn single<T: std::fmt::Display>(value: Option<T>) -> String {
+            value.map_or("-".to_string(), |v| v.to_string())
+        }
+
+        vec![
+            format!("--- Inspetor em 0x{:08X} ({}) ---", self.offset, self.endian.name()),
+            format!(
+                "u8: {}  i8: {}  bits 7-0: {}",
+                single(self.u8),
+                single(self.i8),
+                self.u8.map_or("-".to_string(), |v| format!("{:04b} {:04b}", v >> 4, v & 0xF))
+            ),
+            format!("u16: {}  i16: {}", single(self.u16), single(self.i16)),
+            format!("u32: {}  i32: {}", single(self.u32), single(self.i32)),
+            format!("f32: {}", single(self.f32)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_need_enough_bytes() {
+        let result = inspect(&[0xFE, 0xFF, 0x80, 0x3F], 0x10, Endian::Little);
+        assert_eq!((result.u8, result.i8), (Some(0xFE), Some(-2)));
+        assert_eq!((result.u16, result.i16), (Some(0xFFFE), Some(-2)));
+        assert_eq!((result.u32, result.i32), (Some(0x3F80_FFFE), Some(0x3F80_FFFE)));
+        let short = inspect(&[0x01, 0x02, 0x03], 0, Endian::Little);
+        assert_eq!((short.u16, short.u32, short.f32), (Some(0x0201), None, None));
+        let empty = inspect(&[], 0, Endian::Little);
+        assert_eq!((empty.u8, empty.u16), (None, None));
+    }
+
+    #[test]
+    fn lines_show_a_dash_for_missing_values() {
+        let lines = inspect(&[0xA5], 0x1F2A, Endian::Little).lines();
+        assert_eq!(lines[0], "--- Inspetor em 0x00001F2A (little-endian) ---");
+        assert_eq!(lines[1], "u8: 165  i8: -91  bits 7-0: 1010 0101");
+        assert_eq!(lines[2], "u16: -  i16: -");
+        assert_eq!(lines[4], "f32: -");
+    }
+
+    #[test]
+    fn big_endian_reverses_the_multibyte_values() {
+        assert_eq!(Endian::Little.toggled(), Endian::Big);
+        assert_eq!(Endian::Big.toggled(), Endian::Little);
+        let result = inspect(&[0x3F, 0x80, 0x00, 0x00], 0, Endian::Big);
+        assert_eq!((result.u8, result.u16, result.u32), (Some(0x3F), Some(0x3F80), Some(0x3F80_0000)));
+        assert_eq!(result.f32, Some(1.0));
+        assert!(result.lines()[0].ends_with("(big-endian) ---"));
+    }
+}
@@ -0,0 +1,264 @@
+// Formato de patch IPS: "PATCH", registros e "EOF"
+use std::io;
+
+const HEADER: &[u8] = b"PATCH";
+const FOOTER: &[u8] = b"EOF";
+
+pub enum IpsRecord {
+    // Bytes copiados a partir do offset
+    Data { offset: usize, bytes: Vec<u8> },
+    // Um mesmo byte repetido `len` vezes (registro de tamanho 0)
+    Rle { offset: usize, len: usize, value: u8 },
+}
+
+pub struct IpsPatch {
+    pub records: Vec<IpsRecord>,
+    pub truncate: Option<usize>,  // Extensão opcional após o EOF com o novo tamanho do arquivo
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("patch IPS inválido: {}", message))
+}
+
+// Ler um inteiro big-endian de `len` bytes, avançando a posição
+fn read_be(patch: &[u8], pos: &mut usize, len: usize) -> io::Result<usize> {
+    let bytes = patch.get(*pos..*pos + len).ok_or_else(|| invalid("registro truncado"))?;
+    *pos += len;
+    Ok(bytes.iter().fold(0, |value, &byte| (value << 8) | byte as usize))
+}
+
+pub fn parse(patch: &[u8]) -> io::Result<IpsPatch> {
+    if !patch.starts_with(HEADER) {
+        return Err(invalid("cabeçalho PATCH ausente"));
+    }
+
+    let mut pos = HEADER.len();
+    let mut records = Vec::new();
+    loop {
+        // "EOF" no lugar de um offset encerra os registros
+        if patch.get(pos..pos + 3) == Some(FOOTER) {
+            pos += 3;
+            break;
+        }
+
+        let offset = read_be(patch, &mut pos, 3)?;
+        let size = read_be(patch, &mut pos, 2)?;
+        if size == 0 {
+            let len = read_be(patch, &mut pos, 2)?;
+            let value = read_be(patch, &mut pos, 1)? as u8;
+            records.push(IpsRecord::Rle { offset, len, value });
+        } else {
+            let bytes = patch.get(pos..pos + size).ok_or_else(|| invalid("dados truncados"))?;
+            pos += size;
+            records.push(IpsRecord::Data { offset, bytes: bytes.to_vec() });
+        }
+    }
+
+    let truncate = if patch.len() >= pos + 3 {
+        Some(read_be(patch, &mut pos, 3)?)
+    } else {
+        None
+    };
+    Ok(IpsPatch { records, truncate })
+}
+
+// Maior offset representável em 3 bytes
+const MAX_OFFSET: usize = 0xFF_FFFF;
+// Tamanho máximo de um registro (2 bytes)
+const MAX_RECORD: usize = 0xFFFF;
+// A partir deste tamanho uma sequência de bytes iguais fica menor como RLE (8 bytes) do que como dados
+const RLE_MIN: usize = 9;
+// Offset cujos 3 bytes são "EOF": um registro que começa nele seria lido como o fim do patch
+const EOF_OFFSET: usize = 0x45_4F46;
+
+// Gerar um patch IPS que transforma `original` em `modified`
+pub fn build(original: &[u8], modified: &[u8]) -> io::Result<Vec<u8>> {
+    let changed = |i: usize| original.get(i) != Some(&modified[i]);
+
+    let mut patch = HEADER.to_vec();
+    let mut i = 0;
+    while i < modified.len() {
+        if !changed(i) {
+            i += 1;
+            continue;
+        }
+
+        // Agrupar bytes alterados contíguos em uma única faixa
+        let start = i;
+        while i < modified.len() && changed(i) {
+            i += 1;
+        }
+        write_run(&mut patch, modified, start, i)?;
+    }
+    patch.extend_from_slice(FOOTER);
+
+    // Arquivo menor que o original: extensão de truncamento com o novo tamanho
+    if modified.len() < original.len() {
+        if modified.len() > MAX_OFFSET {
+            return Err(too_large());
+        }
+        patch.extend_from_slice(&(modified.len() as u32).to_be_bytes()[1..]);
+    }
+    Ok(patch)
+}
+
+fn too_large() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "o formato IPS não alcança offsets acima de 16 MB")
+}
+
+// Escrever a faixa alterada `start..end` de `modified` como registros de dados e RLE
+fn write_run(patch: &mut Vec<u8>, modified: &[u8], start: usize, end: usize) -> io::Result<()> {
+    let mut pos = start;
+    let mut data_start = start;
+    while pos < end {
+        let value = modified[pos];
+        let repeat = modified[pos..end].iter().take(MAX_RECORD).take_while(|&&byte| byte == value).count();
+        if repeat >= RLE_MIN {
+            write_data(patch, modified, data_start, pos)?;
+            write_rle(patch, modified, pos, repeat)?;
+            pos += repeat;
+            data_start = pos;
+        } else {
+            pos += repeat;
+        }
+    }
+    write_data(patch, modified, data_start, end)
+}
+
+// Escrever `start..end` como registros de dados, divididos no tamanho máximo de registro
+fn write_data(patch: &mut Vec<u8>, modified: &[u8], start: usize, end: usize) -> io::Result<()> {
+    let mut at = start;
+    while at < end {
+        let at_header = write_header(patch, at)?;
+        // O registro pode ter começado um byte antes; o tamanho continua limitado ao máximo
+        let len = (end - at_header).min(MAX_RECORD);
+        patch.extend_from_slice(&(len as u16).to_be_bytes());
+        patch.extend_from_slice(&modified[at_header..at_header + len]);
+        at = at_header + len;
+    }
+    Ok(())
+}
+
+// Escrever um registro RLE de `len` bytes iguais a partir de `at`
+fn write_rle(patch: &mut Vec<u8>, modified: &[u8], at: usize, len: usize) -> io::Result<()> {
+    if at == EOF_OFFSET {
+        // Um RLE não tem como repetir o byte anterior: o primeiro byte vai num registro de dados antes dele
+        write_data(patch, modified, at, at + 1)?;
+        return write_rle(patch, modified, at + 1, len - 1);
+    }
+    write_header(patch, at)?;
+    patch.extend_from_slice(&0u16.to_be_bytes());
+    patch.extend_from_slice(&(len as u16).to_be_bytes());
+    patch.push(modified[at]);
+    Ok(())
+}
+
+// Escrever o offset de um registro, retornando onde ele começa de fato: um registro em 0x454F46 ("EOF")
+// passa a começar no byte anterior, que quem chama repete do arquivo modificado
+fn write_header(patch: &mut Vec<u8>, offset: usize) -> io::Result<usize> {
+    let offset = if offset == EOF_OFFSET { offset - 1 } else { offset };
+    if offset > MAX_OFFSET {
+        return Err(too_large());
+    }
+    patch.extend_from_slice(&(offset as u32).to_be_bytes()[1..]);
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Aplicar o patch como o editor faz: registros além do fim aumentam o arquivo
+    fn apply(patch: &IpsPatch, original: &[u8]) -> Vec<u8> {
+        let mut data = original.to_vec();
+        for record in &patch.records {
+            let (offset, bytes) = match record {
+                IpsRecord::Data { offset, bytes } => (*offset, bytes.clone()),
+                IpsRecord::Rle { offset, len, value } => (*offset, vec![*value; *len]),
+            };
+            if offset + bytes.len() > data.len() {
+                data.resize(offset + bytes.len(), 0);
+            }
+            data[offset..offset + bytes.len()].copy_from_slice(&bytes);
+        }
+        if let Some(len) = patch.truncate {
+            data.truncate(len);
+        }
+        data
+    }
+
+    fn round_trip(original: &[u8], modified: &[u8]) {
+        let patch = parse(&build(original, modified).unwrap()).unwrap();
+        assert!(patch.records.iter().all(|record| match record {
+            IpsRecord::Data { offset, .. } | IpsRecord::Rle { offset, .. } => *offset != EOF_OFFSET,
+        }));
+        assert!(apply(&patch, original) == modified);
+    }
+
+    // Bytes variados, sem sequências longas que virariam RLE
+    fn varied(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn round_trip_data_rle_grow_and_truncate() {
+        let original = varied(0x1000);
+        let mut modified = original.clone();
+        modified[0x10..0x14].copy_from_slice(&[1, 2, 3, 4]);
+        modified[0x100..0x180].fill(0xAA);
+        round_trip(&original, &modified);
+        modified.extend_from_slice(&[9; 0x20]);
+        round_trip(&original, &modified);
+        round_trip(&original, &original[..0x800]);
+    }
+
+    #[test]
+    fn round_trip_run_starting_at_eof_offset() {
+        let original = vec![0u8; EOF_OFFSET + 0x100];
+        let mut modified = original.clone();
+        modified[EOF_OFFSET..EOF_OFFSET + 0x20].fill(0x33);
+        round_trip(&original, &modified);
+
+        let mut modified = original.clone();
+        modified[EOF_OFFSET..EOF_OFFSET + 4].copy_from_slice(&[1, 2, 3, 4]);
+        round_trip(&original, &modified);
+    }
+
+    #[test]
+    fn round_trip_chunk_split_landing_on_eof_offset() {
+        let start = EOF_OFFSET - MAX_RECORD;
+        let original = vec![0xFFu8; EOF_OFFSET + 0x200];
+        let mut modified = original.clone();
+        modified[start..EOF_OFFSET + 0x100].copy_from_slice(&varied(MAX_RECORD + 0x100));
+        round_trip(&original, &modified);
+    }
+
+    #[test]
+    fn parse_rejects_missing_header_and_truncated_records() {
+        for patch in [
+            &b""[..],
+            b"PATC",
+            b"IPS32EOF",
+            // Sem o EOF
+            b"PATCH",
+            b"PATCH\x00\x00\x10\x00\x02\xAA",
+            // Dados menores que o tamanho do registro
+            b"PATCH\x00\x00\x10\x00\x04\x01\x02EOF",
+            // RLE sem o byte repetido
+            b"PATCH\x00\x00\x10\x00\x00\x00\x04",
+        ] {
+            assert!(parse(patch).is_err(), "{:?}", patch);
+        }
+    }
+
+    #[test]
+    fn parse_reads_records_and_truncation() {
+        let patch = parse(b"PATCH\x00\x00\x10\x00\x02\xAA\xBB\x00\x01\x00\x00\x00\x00\x03\x7FEOF\x00\x20\x00").unwrap();
+        assert!(matches!(&patch.records[..], [
+            IpsRecord::Data { offset: 0x10, bytes },
+            IpsRecord::Rle { offset: 0x100, len: 3, value: 0x7F },
+        ] if bytes == &[0xAA, 0xBB]));
+        assert_eq!(patch.truncate, Some(0x2000));
+        assert_eq!(parse(b"PATCHEOF").unwrap().truncate, None);
+    }
+}
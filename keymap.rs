@@ -0,0 +1,218 @@
+// Atalhos configuráveis: cada ação abaixo associada a uma tecla, com o esquema padrão e o arquivo ~/.pokehex_keys.
+// Só estas ações podem ser remapeadas; os demais comandos têm teclas fixas no loop principal
+// (a ajuda marca com * as configuráveis).
+use minifb::Key;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Start,
+    End,
+    EditByte,
+    Undo,
+    Redo,
+    Save,
+    SaveAs,
+    Open,
+    Quit,
+    Search,
+    SearchText,
+    FindNext,
+    Goto,
+    Help,
+}
+
+// Nome da ação no arquivo de configuração e combinação padrão
+const ACTIONS: &[(Action, &str, &str)] = &[
+    (Action::Up, "mover_cima", "Up"),
+    (Action::Down, "mover_baixo", "Down"),
+    (Action::Left, "mover_esquerda", "Left"),
+    (Action::Right, "mover_direita", "Right"),
+    (Action::PageUp, "pagina_acima", "PageUp"),
+    (Action::PageDown, "pagina_abaixo", "PageDown"),
+    (Action::Start, "inicio", "Home"),
+    (Action::End, "fim", "End"),
+    (Action::EditByte, "editar", "Enter"),
+    (Action::Undo, "desfazer", "Ctrl+Z"),
+    (Action::Redo, "refazer", "Ctrl+Y"),
+    (Action::Save, "salvar", "S"),
+    (Action::SaveAs, "salvar_como", "Ctrl+S"),
+    (Action::Open, "abrir", "O"),
+    (Action::Quit, "sair", "Q"),
+    (Action::Search, "buscar", "/"),
+    (Action::SearchText, "buscar_texto", "T"),
+    (Action::FindNext, "buscar_proximo", "N"),
+    (Action::Goto, "ir_para", "G"),
+    (Action::Help, "ajuda", "Shift+/"),
+];
+
+// Nomes aceitos para as teclas (sem diferenciar maiúsculas)
+const KEY_NAMES: &[(Key, &str)] = &[
+    (Key::A, "A"), (Key::B, "B"), (Key::C, "C"), (Key::D, "D"), (Key::E, "E"), (Key::F, "F"),
+    (Key::G, "G"), (Key::H, "H"), (Key::I, "I"), (Key::J, "J"), (Key::K, "K"), (Key::L, "L"),
+    (Key::M, "M"), (Key::N, "N"), (Key::O, "O"), (Key::P, "P"), (Key::Q, "Q"), (Key::R, "R"),
+    (Key::S, "S"), (Key::T, "T"), (Key::U, "U"), (Key::V, "V"), (Key::W, "W"), (Key::X, "X"),
+    (Key::Y, "Y"), (Key::Z, "Z"),
+    (Key::Key0, "0"), (Key::Key1, "1"), (Key::Key2, "2"), (Key::Key3, "3"), (Key::Key4, "4"),
+    (Key::Key5, "5"), (Key::Key6, "6"), (Key::Key7, "7"), (Key::Key8, "8"), (Key::Key9, "9"),
+    (Key::F1, "F1"), (Key::F2, "F2"), (Key::F3, "F3"), (Key::F4, "F4"), (Key::F5, "F5"), (Key::F6, "F6"),
+    (Key::F7, "F7"), (Key::F8, "F8"), (Key::F9, "F9"), (Key::F10, "F10"), (Key::F11, "F11"), (Key::F12, "F12"),
+    (Key::Up, "Up"), (Key::Down, "Down"), (Key::Left, "Left"), (Key::Right, "Right"),
+    (Key::PageUp, "PageUp"), (Key::PageDown, "PageDown"), (Key::Home, "Home"), (Key::End, "End"),
+    (Key::Enter, "Enter"), (Key::Space, "Space"), (Key::Tab, "Tab"), (Key::Escape, "Escape"),
+    (Key::Backspace, "Backspace"), (Key::Insert, "Insert"), (Key::Delete, "Delete"),
+    (Key::Slash, "Slash"), (Key::Backslash, "Backslash"), (Key::Comma, "Comma"), (Key::Period, "Period"),
+    (Key::Semicolon, "Semicolon"), (Key::Apostrophe, "Apostrophe"), (Key::Minus, "Minus"), (Key::Equal, "Equal"),
+    (Key::LeftBracket, "LeftBracket"), (Key::RightBracket, "RightBracket"),
+];
+
+// Símbolos das teclas de pontuação, aceitos no lugar do nome e usados na exibição ("+" separa os modificadores)
+const KEY_SYMBOLS: &[(Key, &str)] = &[
+    (Key::Slash, "/"), (Key::Backslash, "\\"), (Key::Comma, ","), (Key::Period, "."), (Key::Semicolon, ";"),
+    (Key::Apostrophe, "'"), (Key::Minus, "-"), (Key::LeftBracket, "["), (Key::RightBracket, "]"),
+];
+
+// Tecla com os modificadores exigidos
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Binding {
+    pub key: Key,
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+impl Binding {
+    // Interpretar "Ctrl+S", "Shift+H", "K", "PageUp"...
+    pub fn parse(text: &str) -> Option<Binding> {
+        let mut binding = Binding { key: Key::Unknown, ctrl: false, shift: false };
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let key = parts.pop()?;
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => binding.ctrl = true,
+                "shift" => binding.shift = true,
+                _ => return None,
+            }
+        }
+        binding.key = KEY_NAMES.iter().chain(KEY_SYMBOLS).find(|(_, name)| name.eq_ignore_ascii_case(key))?.0;
+        Some(binding)
+    }
+
+    // Nome da tecla sem os modificadores, com o símbolo no lugar do nome na pontuação
+    pub fn key_name(&self) -> &'static str {
+        KEY_SYMBOLS.iter().chain(KEY_NAMES).find(|(key, _)| *key == self.key).map_or("?", |(_, name)| name)
+    }
+
+    // Teclas que digitam texto; sem Ctrl, no painel ASCII elas escrevem em vez de acionar a ação
+    pub fn types_text(&self) -> bool {
+        !self.ctrl && !matches!(
+            self.key,
+            Key::F1 | Key::F2 | Key::F3 | Key::F4 | Key::F5 | Key::F6 | Key::F7 | Key::F8 | Key::F9 | Key::F10 | Key::F11 | Key::F12
+                | Key::Up | Key::Down | Key::Left | Key::Right | Key::PageUp | Key::PageDown | Key::Home | Key::End
+                | Key::Enter | Key::Tab | Key::Escape | Key::Backspace | Key::Insert | Key::Delete
+        )
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Shift+/ é o "?" do teclado americano
+        if (self.key, self.ctrl, self.shift) == (Key::Slash, false, true) {
+            return write!(f, "?");
+        }
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        write!(f, "{}", self.key_name())
+    }
+}
+
+// Movimento: a tecla vale com qualquer modificador (Shift seleciona, Ctrl avança um passo)
+fn is_movement(action: Action) -> bool {
+    matches!(
+        action,
+        Action::Up | Action::Down | Action::Left | Action::Right
+            | Action::PageUp | Action::PageDown | Action::Start | Action::End
+    )
+}
+
+#[derive(Clone)]
+pub struct KeyMap(HashMap<Action, Binding>);
+
+impl KeyMap {
+    pub fn get(&self, action: Action) -> Binding {
+        self.0[&action]
+    }
+
+    // Verdadeiro se a tecla, com os modificadores pressionados, pertence a alguma ação
+    pub fn claims(&self, key: Key, ctrl: bool, shift: bool) -> bool {
+        self.0.iter().any(|(&action, binding)| {
+            binding.key == key && (is_movement(action) || (binding.ctrl == ctrl && binding.shift == shift))
+        })
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        KeyMap(ACTIONS.iter().map(|&(action, _, default)| (action, Binding::parse(default).unwrap())).collect())
+    }
+}
+
+// Interpretar linhas "ação=tecla"; erros viram avisos e a ação afetada fica com a tecla padrão.
+// Linhas vazias e comentários (#) são ignorados.
+pub fn parse(text: &str) -> (KeyMap, Vec<String>) {
+    let mut keymap = KeyMap::default();
+    let mut warnings = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, key)) = line.split_once('=') else {
+            warnings.push(format!("linha sem '=': {}", line));
+            continue;
+        };
+        let Some(&(action, _, _)) = ACTIONS.iter().find(|(_, n, _)| *n == name.trim()) else {
+            let names: Vec<&str> = ACTIONS.iter().map(|&(_, name, _)| name).collect();
+            warnings.push(format!("ação desconhecida: {} (configuráveis: {})", name.trim(), names.join(", ")));
+            continue;
+        };
+        let Some(binding) = Binding::parse(key) else {
+            warnings.push(format!("tecla inválida para {}: {}", name.trim(), key.trim()));
+            continue;
+        };
+        keymap.0.insert(action, binding);
+    }
+
+    // Duas ações na mesma tecla: a segunda volta ao padrão
+    for (i, &(action, name, default)) in ACTIONS.iter().enumerate() {
+        let binding = keymap.get(action);
+        if let Some(&(_, other, _)) = ACTIONS[..i].iter().find(|(earlier, _, _)| keymap.get(*earlier) == binding) {
+            warnings.push(format!("{} usa a mesma tecla de {} ({})", name, other, binding));
+            keymap.0.insert(action, Binding::parse(default).unwrap());
+        }
+    }
+    (keymap, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_listed_actions_can_be_rebound() {
+        let (keymap, warnings) = parse("preencher=Ctrl+F\nsalvar=F5");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("ação desconhecida: preencher (configuráveis: mover_cima,"), "{}", warnings[0]);
+        assert_eq!(keymap.get(Action::Save), Binding::parse("F5").unwrap());
+    }
+}
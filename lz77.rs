@@ -0,0 +1,161 @@
+// Compressão LZ77 da BIOS do GBA (SWI 0x11/0x12), usada em muitos gráficos e mapas das ROMs de Pokémon
+
+const MAGIC: u8 = 0x10;
+
+// Descompactar o bloco que começa em `offset`: cabeçalho 0x10 + tamanho descompactado (24 bits, little-endian),
+// depois grupos de 8 itens precedidos por um byte de flags (bit 7 primeiro). Flag 0 é um byte literal;
+// flag 1 são 2 bytes com o tamanho (4 bits + 3) e a distância para trás (12 bits + 1) de uma cópia.
+// None se o cabeçalho, uma distância ou o fim dos dados não fizerem sentido.
+pub fn decompress(data: &[u8], offset: usize) -> Option<Vec<u8>> {
+    decode(data, offset).map(|(out, _)| out)
+}
+
+// Bytes ocupados pelo bloco compactado que começa em `offset`, cabeçalho incluído
+pub fn compressed_len(data: &[u8], offset: usize) -> Option<usize> {
+    decode(data, offset).map(|(_, len)| len)
+}
+
+fn decode(data: &[u8], offset: usize) -> Option<(Vec<u8>, usize)> {
+    let header = data.get(offset..offset.checked_add(4)?)?;
+    if header[0] != MAGIC {
+        return None;
+    }
+    let size = u32::from_le_bytes([header[1], header[2], header[3], 0]) as usize;
+    if size == 0 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(size);
+    let mut pos = offset + 4;
+    while out.len() < size {
+        let flags = *data.get(pos)?;
+        pos += 1;
+        for bit in (0..8).rev() {
+            if out.len() >= size {
+                break;
+            }
+            if flags & (1 << bit) == 0 {
+                out.push(*data.get(pos)?);
+                pos += 1;
+            } else {
+                let pair = data.get(pos..pos + 2)?;
+                pos += 2;
+                let len = (pair[0] >> 4) as usize + 3;
+                let distance = (((pair[0] & 0x0F) as usize) << 8 | pair[1] as usize) + 1;
+                let start = out.len().checked_sub(distance)?;
+                // A cópia pode sobrepor o que ela mesma escreve (ex: repetir um único byte)
+                for i in 0..len {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+    // A última cópia pode passar do tamanho declarado
+    out.truncate(size);
+    Some((out, pos - offset))
+}
+
+// Compactar no mesmo formato, procurando a cópia mais longa na janela de 4 KB (busca gulosa).
+// A distância mínima é 2: a descompactação da BIOS direto para a VRAM escreve de 2 em 2 bytes
+// e não consegue copiar do byte anterior. A saída é completada com zeros até um múltiplo de 4.
+// None se não houver dados ou se eles não couberem no tamanho de 24 bits do cabeçalho.
+pub fn compress(input: &[u8]) -> Option<Vec<u8>> {
+    if input.is_empty() || input.len() >= 1 << 24 {
+        return None;
+    }
+    let size = input.len() as u32;
+    let mut out = vec![MAGIC, size as u8, (size >> 8) as u8, (size >> 16) as u8];
+    let mut pos = 0;
+    while pos < input.len() {
+        let flags_at = out.len();
+        out.push(0);
+        for bit in (0..8).rev() {
+            if pos >= input.len() {
+                break;
+            }
+            let (len, distance) = longest_match(input, pos);
+            if len >= 3 {
+                out[flags_at] |= 1 << bit;
+                let distance = distance - 1;
+                out.push(((len - 3) << 4) as u8 | (distance >> 8) as u8);
+                out.push(distance as u8);
+                pos += len;
+            } else {
+                out.push(input[pos]);
+                pos += 1;
+            }
+        }
+    }
+    while out.len() % 4 != 0 {
+        out.push(0);
+    }
+    Some(out)
+}
+
+// Maior repetição (tamanho até 18, distância de 2 a 4096) dos bytes em `pos`
+fn longest_match(input: &[u8], pos: usize) -> (usize, usize) {
+    let max_len = (input.len() - pos).min(18);
+    let mut best = (0, 0);
+    for distance in 2..=pos.min(4096) {
+        let start = pos - distance;
+        let len = (0..max_len).take_while(|&i| input[start + i] == input[pos + i]).count();
+        if len > best.0 {
+            best = (len, distance);
+            if len == max_len {
+                break;
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "ABABAB": dois literais e uma cópia de 4 bytes a 2 para trás
+    const BLOCK: [u8; 9] = [0x10, 0x06, 0x00, 0x00, 0x20, b'A', b'B', 0x10, 0x01];
+
+    #[test]
+    fn decompress_reads_literals_and_copies() {
+        let mut data = vec![0xFF; 3];
+        data.extend_from_slice(&BLOCK);
+        assert_eq!(decompress(&data, 3).unwrap(), b"ABABAB");
+        assert_eq!(compressed_len(&data, 3), Some(BLOCK.len()));
+    }
+
+    #[test]
+    fn decompress_rejects_invalid_and_truncated_blocks() {
+        let mut wrong_magic = BLOCK;
+        wrong_magic[0] = 0x11;
+        let mut empty = BLOCK;
+        empty[1] = 0;
+        // Cópia antes do início dos dados descompactados
+        let before_start = [0x10, 0x03, 0x00, 0x00, 0x80, 0x00, 0x00];
+        for data in [&wrong_magic[..], &empty, &before_start, &BLOCK[..BLOCK.len() - 1], &BLOCK[..3]] {
+            assert_eq!(decompress(data, 0), None, "{:02X?}", data);
+        }
+        assert_eq!(decompress(&BLOCK, BLOCK.len()), None);
+        assert_eq!(decompress(&BLOCK, usize::MAX), None);
+    }
+
+    #[test]
+    fn compress_round_trips() {
+        let varied: Vec<u8> = (0..0x2345).map(|i| (i * 7 % 251) as u8).collect();
+        let repeated: Vec<u8> = b"ABC".iter().cycle().take(0x1003).copied().collect();
+        for input in [&b"x"[..], b"AA", &[0; 0x100], &varied, &repeated] {
+            let block = compress(input).unwrap();
+            assert_eq!(block.len() % 4, 0);
+            assert_eq!(decompress(&block, 0).as_deref(), Some(input));
+            assert!(compressed_len(&block, 0).is_some_and(|len| len <= block.len()));
+        }
+        // Bytes repetidos compactam bem mesmo sem copiar do byte anterior
+        assert!(compress(&[0; 0x100]).unwrap().len() < 0x40);
+    }
+
+    #[test]
+    fn compress_rejects_what_the_header_cannot_describe() {
+        assert_eq!(compress(&[]), None);
+        assert_eq!(compress(&vec![0; 1 << 24]), None);
+    }
+}
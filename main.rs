@@ -1,283 +1,5457 @@
-use minifb::{Key, Window, WindowOptions};
-use std::fs;
-use std::io::{self, Write};
-use std::path::PathBuf;
+mod buffer;
+mod checksum;
+mod games;
+mod header;
+mod help;
+mod inspect;
+mod ips;
+mod keymap;
+mod lz77;
+mod minimap;
+mod script;
+mod table;
+mod template;
+mod theme;
+mod tiles;
 
-const WIDTH: usize = 80;      // Colunas de caracteres
-const HEIGHT: usize = 40;     // Linhas de caracteres
-const BYTES_PER_ROW: usize = 16;
+use buffer::Buffer;
+use checksum::Checksums;
+use games::GameId;
+use header::{Console, RomHeader};
+use inspect::{Endian, InspectResult};
+use ips::IpsRecord;
+use keymap::{Action, KeyMap};
+use theme::Theme;
+use minifb::{InputCallback, Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const WIDTH: usize = 80;      // Colunas de caracteres da janela inicial
+const HEIGHT: usize = 40;     // Linhas de caracteres da janela inicial
+const BYTES_PER_ROW: usize = 16;   // Valor padrão de bytes por linha
+const GBA_ROM_BASE: usize = 0x0800_0000;  // Endereço da ROM no mapa de memória do GBA
+const MAX_RECENT_FILES: usize = 10;
+const CHAR_WIDTH: usize = 10;  // Largura de um caractere em pixels
+const CHAR_HEIGHT: usize = 20; // Altura de um caractere em pixels
+const STATUS_DURATION: Duration = Duration::from_secs(4);  // Tempo que uma mensagem fica no rodapé
+const REPEAT_DELAY: Duration = Duration::from_millis(400);   // Tempo segurando uma tecla até ela começar a repetir
+const REPEAT_INTERVAL: Duration = Duration::from_millis(50); // Intervalo entre as repetições
+const NAV_HISTORY_LEN: usize = 100;  // Posições guardadas para voltar com Ctrl+[
+const MINIMAP_WIDTH: usize = 24;  // Largura em pixels da coluna do minimapa, na direita da janela
+const PALETTE_COLUMNS: usize = 16;  // Cores por linha na visão de paleta (uma paleta de 4bpp)
+const TEMPLATE_FIELDS_PER_LINE: usize = 4;  // Campos do modelo por linha do painel
+const STDIN_PATH: &str = "-";  // Caminho que lê os dados da entrada padrão (ex: de um pipe)
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);  // Intervalo padrão da cópia de recuperação
+
+// Padrão da última busca, para "buscar próximo"
+enum SearchPattern {
+    Bytes(Vec<Option<u8>>),  // None é um curinga (??)
+    Text(String),
+}
+
+// Operações bit a bit aplicáveis a um intervalo
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum BitOp {
+    And,
+    Or,
+    Xor,
+    Not,  // Ignora a máscara
+}
+
+impl BitOp {
+    fn apply(self, byte: u8, mask: u8) -> u8 {
+        match self {
+            BitOp::And => byte & mask,
+            BitOp::Or => byte | mask,
+            BitOp::Xor => byte ^ mask,
+            BitOp::Not => !byte,
+        }
+    }
+}
+
+// Como o painel ASCII mostra bytes não imprimíveis
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AsciiMode {
+    Dots,   // "." para tudo que não é imprimível
+    Caret,  // ^@..^_ para 0x00-0x1F e um símbolo para 0x7F-0xFF
+    Hex,    // O próprio valor em hexadecimal
+}
+
+impl AsciiMode {
+    fn next(self) -> AsciiMode {
+        match self {
+            AsciiMode::Dots => AsciiMode::Caret,
+            AsciiMode::Caret => AsciiMode::Hex,
+            AsciiMode::Hex => AsciiMode::Dots,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            AsciiMode::Dots => "pontos",
+            AsciiMode::Caret => "notação ^",
+            AsciiMode::Hex => "hexadecimal",
+        }
+    }
+
+    // Colunas ocupadas por byte no painel
+    fn cell_width(self) -> usize {
+        match self {
+            AsciiMode::Dots => 1,
+            AsciiMode::Caret | AsciiMode::Hex => 2,
+        }
+    }
+
+    // Texto de um byte, com a largura da célula
+    fn glyph(self, byte: u8) -> String {
+        let printable = (32..=126).contains(&byte);
+        match self {
+            AsciiMode::Dots if printable => (byte as char).to_string(),
+            AsciiMode::Dots => ".".to_string(),
+            _ if printable => format!("{} ", byte as char),
+            AsciiMode::Caret if byte < 0x20 => format!("^{}", (byte + 0x40) as char),
+            AsciiMode::Caret => "• ".to_string(),
+            AsciiMode::Hex => format!("{:02X}", byte),
+        }
+    }
+}
+
+// Se digitar e colar substituem os bytes sob o cursor ou os inserem, deslocando os seguintes
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EditMode {
+    Overwrite,
+    Insert,
+}
+
+impl EditMode {
+    fn toggled(self) -> EditMode {
+        match self {
+            EditMode::Overwrite => EditMode::Insert,
+            EditMode::Insert => EditMode::Overwrite,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            EditMode::Overwrite => "sobrescrever",
+            EditMode::Insert => "inserir",
+        }
+    }
+}
+
+// Base em que os endereços são exibidos e digitados
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AddressBase {
+    Hex,
+    Decimal,
+}
+
+impl AddressBase {
+    fn toggled(self) -> AddressBase {
+        match self {
+            AddressBase::Hex => AddressBase::Decimal,
+            AddressBase::Decimal => AddressBase::Hex,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            AddressBase::Hex => "hexadecimal",
+            AddressBase::Decimal => "decimal",
+        }
+    }
+
+    // Endereço com 10 caracteres, a largura da coluna; `digits` diminui para caber um sinal
+    fn format(self, offset: usize, digits: usize) -> String {
+        match self {
+            AddressBase::Hex => format!("0x{:0width$X}", offset, width = digits),
+            AddressBase::Decimal => format!("{:0width$}", offset, width = digits + 2),
+        }
+    }
+
+    // Interpretar um endereço; em decimal, o prefixo 0x ainda permite digitar em hexadecimal
+    fn parse(self, input: &str) -> Option<usize> {
+        match self {
+            AddressBase::Hex => parse_offset(input),
+            AddressBase::Decimal if input.starts_with("0x") || input.starts_with("0X") => parse_offset(input),
+            AddressBase::Decimal => input.parse().ok(),
+        }
+    }
+}
+
+// O que fazer com a resposta de uma pergunta do rodapé; as perguntas encadeadas levam o que já foi respondido
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum PromptAction {
+    Open,
+    SaveAs,
+    Goto,
+    SearchBytes,
+    SearchText,
+    Recovery(PathBuf),              // Restaurar a cópia de recuperação encontrada ao abrir
+    Quit,                           // Sair com alterações não salvas
+    CloseTab,                       // Fechar a aba com alterações não salvas
+    RomSize { save_as: bool },      // Salvar uma ROM GBA fora de uma potência de 2
+    Reload,
+    Reference,
+    Snapshot,
+    RestoreSnapshot,
+    DumpScope,
+    DumpPath(Option<(usize, usize)>),
+    CArrayRange,
+    CArrayName(usize, usize),
+    CArrayPath(usize, usize, String),
+    ApplyIps,
+    CreateIps,
+    Recent,
+    Table,
+    ReplaceFind,
+    ReplaceWith(Vec<u8>),
+    ReplaceConfirm(Vec<u8>, Vec<u8>),
+    Count,
+    Resize,
+    ExpandSize,
+    TrimByte,
+    TrimConfirm(u8),
+    Bookmark(usize),
+    Terminators,
+    KnownOffset,
+    WriteInt(usize),
+    WritePointer(usize),
+    FreeSpace,
+    EditByte(usize),
+    Fill(usize, usize),
+    Stride,
+    RecordSize,
+    Bitwise(usize, usize),
+    BitMask(usize, usize, BitOp),
+    DefaultFill,
+    Template,
+    TemplateField(usize),           // Offset do campo sob o cursor
+    Script,
+    Lz77(usize, Vec<u8>),           // Bloco no offset e os dados já descompactados
+    Lz77Save(Vec<u8>),
+    Lz77Source(usize),
+    Lz77Confirm(usize, Vec<u8>),    // Dados a compactar sobre o bloco maior que o original
+    ExportRange,
+    ExportBin(usize, usize),
+    Import(usize),
+    ImportConfirm(usize, Vec<u8>, String),  // Arquivo que passa do fim dos dados
+    EditText(usize, usize),         // Offset e tamanho do texto atual
+    TextConfirm(usize, Vec<u8>),    // Texto codificado maior que o original
+}
+
+impl PromptAction {
+    // Perguntas em que a resposta vazia também significa algo (um padrão ou o cancelamento avisado)
+    fn keeps_empty(&self) -> bool {
+        matches!(
+            self,
+            PromptAction::Recovery(_)
+                | PromptAction::Quit
+                | PromptAction::DumpScope
+                | PromptAction::CArrayName(..)
+                | PromptAction::ReplaceConfirm(..)
+                | PromptAction::TrimByte
+                | PromptAction::Bookmark(_)
+                | PromptAction::Template
+                | PromptAction::Lz77(..)
+                | PromptAction::Lz77Source(_)
+                | PromptAction::Lz77Confirm(..)
+                | PromptAction::ImportConfirm(..)
+                | PromptAction::TextConfirm(..)
+        )
+    }
+}
+
+// O que a janela desenha em pixels a partir do cursor
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GraphicsView {
+    Tiles,    // Tiles 4bpp
+    Palette,  // Cores BGR555, 16 por linha
+}
+
+// Pergunta digitada no rodapé da própria janela, sem travar a tela como o terminal
+struct Prompt {
+    label: String,
+    text: String,
+    action: PromptAction,
+}
+
+// Painel sem foco da visão dividida; o painel com foco usa view_offset e cursor_pos do próprio editor
+#[derive(Clone, Copy)]
+struct SplitView {
+    view_offset: usize,
+    cursor: usize,      // Offset do cursor do painel
+    focus_right: bool,  // O painel da direita é o que tem o foco
+}
+
+// Estado de um arquivo aberto em uma aba. O da aba ativa fica nos próprios campos do HexEditor;
+// a posição dela em `tabs` guarda um documento vazio até a troca de aba
+#[derive(Default)]
+struct Document {
+    rom_path: Option<PathBuf>,
+    data: Buffer,
+    modified: bool,
+    view_offset: usize,
+    cursor_pos: (usize, usize),
+    cursor_in_ascii: bool,
+    bytes_per_row: usize,
+    undo_stack: Vec<(usize, u8)>,
+    redo_stack: Vec<(usize, u8)>,
+    saved_depth: Option<usize>,
+    pending_nibble: Option<u8>,
+    selection: Option<(usize, usize)>,
+    bookmarks: Vec<(usize, String)>,
+    session_edits: HashSet<usize>,
+    jump_stack: Vec<usize>,
+    nav_back: Vec<(usize, usize)>,
+    nav_forward: Vec<(usize, usize)>,
+    locked_ranges: Vec<(usize, usize)>,
+    base_offset: Option<usize>,
+    original_len: usize,
+    original: Option<Vec<u8>>,
+    split: Option<SplitView>,
+    default_fill: u8,
+    snapshots: HashMap<String, Vec<u8>>,
+}
+
+impl Document {
+    fn new(bytes_per_row: usize) -> Self {
+        Self {
+            cursor_pos: (10, 0),
+            bytes_per_row,
+            saved_depth: Some(0),
+            ..Default::default()
+        }
+    }
+}
 
 struct HexEditor {
     rom_path: Option<PathBuf>,
-    data: Vec<u8>,
+    data: Buffer,
     modified: bool,
     view_offset: usize,
     cursor_pos: (usize, usize),  // Posição do cursor (x, y)
+    undo_stack: Vec<(usize, u8)>,  // (offset, valor anterior)
+    redo_stack: Vec<(usize, u8)>,  // (offset, valor antes de desfazer)
+    saved_depth: Option<usize>,    // Tamanho da pilha de desfazer no último salvamento
+    pending_nibble: Option<u8>,    // Nibble alto já digitado, aguardando o baixo
+    last_search: Option<SearchPattern>,
+    ignore_case: bool,             // Busca de texto sem diferenciar maiúsculas
+    selection: Option<(usize, usize)>,  // (início, fim) da seleção, ambos inclusivos
+    clipboard: Vec<u8>,
+    backup_on_save: bool,          // Copiar o arquivo original para .bak na primeira gravação
+    warn_rom_size: bool,           // Perguntar ao salvar ROMs GBA fora de uma potência de 2 (desativável na sessão)
+    show_header: bool,             // Exibir o painel com o cabeçalho do cartucho
+    table: Option<HashMap<u8, String>>,  // Tabela de caracteres (.tbl) para o painel de texto
+    bytes_per_row: usize,
+    jump_stack: Vec<usize>,        // Offsets de origem dos ponteiros seguidos
+    nav_back: Vec<(usize, usize)>,     // (view_offset, offset do cursor) antes de cada salto
+    nav_forward: Vec<(usize, usize)>,  // Posições deixadas ao voltar, para avançar de novo
+    locked_ranges: Vec<(usize, usize)>,  // Intervalos (inclusivos, ordenados) protegidos contra edição
+    reference: Option<Vec<u8>>,    // Segundo arquivo para comparação byte a byte
+    cursor_in_ascii: bool,         // Cursor no painel ASCII em vez do hexadecimal
+    read_only: bool,               // Bloquear edições e gravação
+    bookmarks: Vec<(usize, String)>,  // (offset, rótulo), ordenados por offset
+    use_mmap: bool,                // Mapear os arquivos abertos em vez de lê-los para a memória
+    use_stream: bool,              // Ler do disco só o trecho em uso, para arquivos grandes demais para a memória
+    base_offset: Option<usize>,    // Base para exibir os endereços como relativos
+    session_edits: HashSet<usize>, // Offsets editados nesta sessão, destacados na grade
+    endianness: Endian,            // Ordem dos bytes no inspetor de dados
+    ascii_mode: AsciiMode,
+    tabs: Vec<Document>,           // Arquivos abertos, um por aba
+    active_tab: usize,
+    status_message: Option<(String, Instant)>,  // Mensagem do rodapé e quando foi definida
+    theme: Theme,
+    stride: usize,                 // Bytes por passo de Ctrl+Setas (4 = um ponteiro GBA)
+    struct_size: Option<usize>,    // Tamanho dos registros de uma tabela, para Ctrl+Cima/Baixo
+    struct_base: usize,            // Offset do primeiro registro da tabela
+    int_width: usize,              // Bytes do inteiro editado em decimal com Shift+W
+    address_base: AddressBase,     // Endereços da coluna, do rodapé e do "ir para"
+    free_byte: u8,                 // Byte de preenchimento do espaço livre (0xFF na maioria das ROMs)
+    text_codes: table::TextCodes,  // Terminadores e códigos de controle dos textos
+    template: Option<(template::Template, usize)>,  // Modelo de estrutura aplicado e o offset onde começa
+    original_len: usize,           // Tamanho do arquivo ao ser lido do disco
+    original: Option<Vec<u8>>,     // Cópia dos dados da última gravação, para contar as diferenças (arquivos no disco usam o próprio arquivo)
+    diff_cache: Cell<Option<usize>>,  // Resultado de diff_count até a próxima edição
+    gb_checksum_cache: Cell<Option<u16>>,  // Checksum global GB até a próxima edição (o cabeçalho o mostra a cada quadro)
+    default_fill: u8,              // Byte usado ao inserir e ao completar o arquivo
+    edit_mode: EditMode,
+    split: Option<SplitView>,      // Visão dividida comparando dois trechos do arquivo
+    snapshots: HashMap<String, Vec<u8>>,  // Cópias nomeadas dos dados, só em memória
+    autosave_interval: Option<Duration>,  // Intervalo da cópia de recuperação (None desativa)
+    prompt: Option<Prompt>,        // Pergunta aberta no rodapé
+    quitting: bool,                // A saída foi confirmada no rodapé
+    dirty: bool,                   // A tela precisa ser redesenhada
+    screen_rows: usize,            // Linhas de caracteres que cabem na janela
+    graphics_view: Option<GraphicsView>,  // Gráficos desenhados na janela a partir do cursor
+    tiles_wide: usize,             // Tiles por linha na visão de gráficos
+    tile_palette: Option<(usize, [u32; 16])>,  // Offset e cores da paleta dos tiles (None = tons de cinza)
+    show_minimap: bool,            // Minimapa da entropia do arquivo na janela
+    cursor_blink: Option<Duration>,  // Meio período do cursor piscando na janela (None = sempre aceso)
+    minimap: RefCell<Option<(usize, Vec<u32>)>>,  // Cores do minimapa e a altura para a qual foram calculadas
+    last_autosave: Instant,
+    started: Instant,              // Referência da fase do cursor piscando
+    show_help: bool,               // Ajuda com os atalhos por cima da grade
+    keymap: KeyMap,                // Teclas das ações configuráveis
+    config_dir: Option<PathBuf>,   // Pasta da configuração e do estado (a do usuário); None sem HOME
 }
 
 impl HexEditor {
     fn new() -> Self {
         Self {
             rom_path: None,
-            data: Vec::new(),
+            data: Buffer::from(Vec::new()),
             modified: false,
             view_offset: 0,
-            cursor_pos: (0, 0),
+            cursor_pos: (10, 0),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            saved_depth: Some(0),
+            pending_nibble: None,
+            last_search: None,
+            ignore_case: false,
+            selection: None,
+            clipboard: Vec::new(),
+            backup_on_save: true,
+            warn_rom_size: true,
+            show_header: false,
+            table: None,
+            bytes_per_row: BYTES_PER_ROW,
+            jump_stack: Vec::new(),
+            nav_back: Vec::new(),
+            nav_forward: Vec::new(),
+            locked_ranges: Vec::new(),
+            reference: None,
+            cursor_in_ascii: false,
+            read_only: false,
+            bookmarks: Vec::new(),
+            use_mmap: false,
+            use_stream: false,
+            base_offset: None,
+            session_edits: HashSet::new(),
+            endianness: Endian::Little,
+            ascii_mode: AsciiMode::Dots,
+            tabs: vec![Document::default()],
+            active_tab: 0,
+            status_message: None,
+            theme: Theme::default(),
+            stride: 4,
+            struct_size: None,
+            struct_base: 0,
+            int_width: 2,
+            address_base: AddressBase::Hex,
+            free_byte: 0xFF,
+            text_codes: table::TextCodes::default(),
+            template: None,
+            original_len: 0,
+            original: None,
+            diff_cache: Cell::new(None),
+            gb_checksum_cache: Cell::new(None),
+            default_fill: 0x00,
+            edit_mode: EditMode::Overwrite,
+            split: None,
+            snapshots: HashMap::new(),
+            autosave_interval: Some(AUTOSAVE_INTERVAL),
+            prompt: None,
+            quitting: false,
+            dirty: true,
+            screen_rows: HEIGHT,
+            graphics_view: None,
+            tiles_wide: 16,
+            tile_palette: None,
+            show_minimap: false,
+            cursor_blink: None,
+            minimap: RefCell::new(None),
+            last_autosave: Instant::now(),
+            started: Instant::now(),
+            show_help: false,
+            keymap: KeyMap::default(),
+            config_dir: home_dir(),
         }
     }
 
-    // Abrir um arquivo ROM
-    fn open_file(&mut self, path: &str) -> io::Result<()> {
-        let data = fs::read(path)?;
-        self.data = data;
-        self.rom_path = Some(PathBuf::from(path));
-        self.view_offset = 0;
-        self.modified = false;
-        Ok(())
+    // Trocar o estado do arquivo ativo pelo de um documento guardado
+    fn swap_document(&mut self, doc: &mut Document) {
+        self.data_changed();
+        mem::swap(&mut self.rom_path, &mut doc.rom_path);
+        mem::swap(&mut self.data, &mut doc.data);
+        mem::swap(&mut self.modified, &mut doc.modified);
+        mem::swap(&mut self.view_offset, &mut doc.view_offset);
+        mem::swap(&mut self.cursor_pos, &mut doc.cursor_pos);
+        mem::swap(&mut self.cursor_in_ascii, &mut doc.cursor_in_ascii);
+        mem::swap(&mut self.bytes_per_row, &mut doc.bytes_per_row);
+        mem::swap(&mut self.undo_stack, &mut doc.undo_stack);
+        mem::swap(&mut self.redo_stack, &mut doc.redo_stack);
+        mem::swap(&mut self.saved_depth, &mut doc.saved_depth);
+        mem::swap(&mut self.pending_nibble, &mut doc.pending_nibble);
+        mem::swap(&mut self.selection, &mut doc.selection);
+        mem::swap(&mut self.bookmarks, &mut doc.bookmarks);
+        mem::swap(&mut self.session_edits, &mut doc.session_edits);
+        mem::swap(&mut self.jump_stack, &mut doc.jump_stack);
+        mem::swap(&mut self.nav_back, &mut doc.nav_back);
+        mem::swap(&mut self.nav_forward, &mut doc.nav_forward);
+        mem::swap(&mut self.locked_ranges, &mut doc.locked_ranges);
+        mem::swap(&mut self.base_offset, &mut doc.base_offset);
+        mem::swap(&mut self.original_len, &mut doc.original_len);
+        mem::swap(&mut self.original, &mut doc.original);
+        mem::swap(&mut self.split, &mut doc.split);
+        mem::swap(&mut self.snapshots, &mut doc.snapshots);
+        mem::swap(&mut self.default_fill, &mut doc.default_fill);
     }
 
-    // Salvar as alterações no arquivo
-    fn save_file(&mut self) -> io::Result<()> {
-        if let Some(ref path) = self.rom_path {
-            fs::write(path, &self.data)?;
-            self.modified = false;
-            println!("Arquivo salvo: {}", path.display());
+    // Ativar a aba `index`, guardando o estado da atual
+    fn switch_tab(&mut self, index: usize) {
+        if index >= self.tabs.len() || index == self.active_tab {
+            return;
         }
-        Ok(())
+        let mut current = mem::take(&mut self.tabs[self.active_tab]);
+        self.swap_document(&mut current);
+        self.tabs[self.active_tab] = current;
+
+        let mut next = mem::take(&mut self.tabs[index]);
+        self.swap_document(&mut next);
+        self.tabs[index] = next;
+        self.active_tab = index;
     }
 
-    // Modificar um byte
-    fn edit_byte(&mut self, offset: usize, value: u8) {
-        if offset < self.data.len() {
-            self.data[offset] = value;
-            self.modified = true;
+    // Criar uma aba vazia no fim e ativá-la
+    fn new_tab(&mut self) {
+        self.tabs.push(Document::new(self.bytes_per_row));
+        self.switch_tab(self.tabs.len() - 1);
+    }
+
+    // Fechar a aba ativa, descartando suas alterações; fechar a única aba a deixa vazia
+    fn close_tab(&mut self) {
+        let mut empty = Document::new(self.bytes_per_row);
+        self.swap_document(&mut empty);
+        if self.tabs.len() > 1 {
+            self.tabs.remove(self.active_tab);
+            let index = self.active_tab.min(self.tabs.len() - 1);
+            let mut next = mem::take(&mut self.tabs[index]);
+            self.swap_document(&mut next);
+            self.tabs[index] = next;
+            self.active_tab = index;
         }
     }
 
-    // Calcular o offset absoluto baseado na posição do cursor
-    fn get_cursor_offset(&self) -> Option<usize> {
-        let (x, y) = self.cursor_pos;
-        
-        // Verificar se o cursor está na área de bytes (não no endereço ou ASCII)
-        if x >= 10 && x < 10 + BYTES_PER_ROW * 3 && x % 3 != 2 {
-            let byte_idx = (x - 10) / 3;
-            let offset = self.view_offset + y * BYTES_PER_ROW + byte_idx;
-            
-            if offset < self.data.len() {
-                return Some(offset);
+    // Alguma aba tem alterações não salvas
+    fn any_modified(&self) -> bool {
+        self.modified
+            || self
+                .tabs
+                .iter()
+                .enumerate()
+                .any(|(i, doc)| i != self.active_tab && doc.modified)
+    }
+
+    // Salvar todas as abas modificadas, voltando à aba ativa no fim
+    fn save_all(&mut self) -> io::Result<()> {
+        let active = self.active_tab;
+        let mut result = Ok(());
+        for i in 0..self.tabs.len() {
+            self.switch_tab(i);
+            if self.modified {
+                result = self.save_file();
+                if result.is_err() {
+                    break;
+                }
             }
         }
-        
-        None
+        self.switch_tab(active);
+        result
     }
 
-    // Mover o cursor
-    fn move_cursor(&mut self, dx: isize, dy: isize) {
-        let new_x = (self.cursor_pos.0 as isize + dx).max(0).min((10 + BYTES_PER_ROW * 3 - 1) as isize) as usize;
-        let new_y = (self.cursor_pos.1 as isize + dy).max(0).min((HEIGHT - 1) as isize) as usize;
-        
-        self.cursor_pos = (new_x, new_y);
+    // Hora de gravar as cópias de recuperação
+    fn autosave_due(&self) -> bool {
+        self.autosave_interval.is_some_and(|interval| self.last_autosave.elapsed() >= interval)
     }
 
-    // Rolar a visualização
-    fn scroll(&mut self, delta: isize) {
-        if delta < 0 {
-            self.view_offset = self.view_offset.saturating_sub(BYTES_PER_ROW);
-        } else if delta > 0 {
-            let max_offset = self.data.len().saturating_sub(HEIGHT * BYTES_PER_ROW);
-            self.view_offset = (self.view_offset + BYTES_PER_ROW).min(max_offset);
+    // Gravar os dados das abas modificadas em rom.gba.recovery, sem tocar no original; retorna quantas foram gravadas
+    fn autosave(&mut self) -> io::Result<usize> {
+        self.last_autosave = Instant::now();
+        let active = self.active_tab;
+        let mut result = Ok(0);
+        for i in 0..self.tabs.len() {
+            self.switch_tab(i);
+            if let (true, Some(path)) = (self.modified, self.disk_path()) {
+                match File::create(sibling_path(path, ".recovery")).and_then(|mut file| self.data.write_to(&mut file)) {
+                    Ok(()) => result = result.map(|count| count + 1),
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }
         }
+        self.switch_tab(active);
+        result
     }
 
-    // Renderizar o conteúdo do editor para o terminal
-    fn render(&self) {
-        // Limpar tela
-        print!("\x1B[2J\x1B[1;1H");
-        
-        // Cabeçalho
-        println!("=== Editor Hexadecimal para ROMs de Pokémon ===");
-        if let Some(ref path) = self.rom_path {
-            println!("Arquivo: {} ({}{})", 
-                    path.display(), 
-                    self.data.len(), 
-                    if self.modified { ", modificado" } else { "" });
-        } else {
-            println!("Nenhum arquivo aberto");
+    // Cópia de recuperação do arquivo ativo, se ela for mais recente que o próprio arquivo
+    fn recovery_file(&self) -> Option<PathBuf> {
+        let path = self.disk_path()?;
+        let recovery = sibling_path(path, ".recovery");
+        let recovery_time = fs::metadata(&recovery).and_then(|meta| meta.modified()).ok()?;
+        let rom_time = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+        (recovery_time > rom_time).then_some(recovery)
+    }
+
+    // Trocar os dados pelos da cópia de recuperação, que ficam como alterações ainda não salvas
+    fn restore_recovery(&mut self, recovery: &Path) -> io::Result<()> {
+        if self.read_only {
+            return Err(read_only_error());
         }
-        println!("-------------------------------------------------------------------------------");
-        
-        // Instruções
-        println!("Comandos: Setas (mover), PgUp/PgDn (rolar), Enter (editar), S (salvar), O (abrir), Q (sair)");
-        println!("-------------------------------------------------------------------------------");
-        
-        if self.data.is_empty() {
-            println!("Nenhum dado para exibir. Use 'O' para abrir um arquivo.");
-            return;
+        if !self.data.can_resize() {
+            return Err(fixed_size_error());
         }
-        
-        // Cabeçalho da tabela
-        print!("Offset    | ");
-        for i in 0..BYTES_PER_ROW {
-            print!("{:02X} ", i);
+        let bytes = fs::read(recovery)?;
+        if self.replacement_touches_lock(&bytes) {
+            return Err(locked_error());
         }
-        println!("| ASCII");
-        println!("-----------+-------------------------------------------------+-----------------");
-        
-        // Linhas de dados
-        let visible_rows = HEIGHT - 10; // Ajustar para as linhas de cabeçalho
-        let end_offset = std::cmp::min(
-            self.view_offset + visible_rows * BYTES_PER_ROW,
-            self.data.len()
-        );
-        
-        let mut row_offset = self.view_offset;
-        let mut display_row = 0;
-        
-        while row_offset < end_offset {
-            let row_end = std::cmp::min(row_offset + BYTES_PER_ROW, self.data.len());
-            
-            // Endereço
-            print!("0x{:08X} |", row_offset);
-            
-            // Bytes em hexadecimal
-            for i in row_offset..row_end {
-                let is_cursor_here = self.cursor_pos == ((i - row_offset) * 3 + 10, display_row);
-                
-                if is_cursor_here {
-                    print!(" \x1B[7m{:02X}\x1B[0m", self.data[i]); // Inverter cores para o cursor
+        *self.data_vec() = bytes;
+        self.session_edits.clear();
+        self.selection = None;
+        self.pending_nibble = None;
+        self.invalidate_history();
+        self.clamp_view();
+        self.clamp_cursor();
+        Ok(())
+    }
+
+    // Barra de abas: número, nome do arquivo e "*" para alterações não salvas
+    fn tab_bar(&self) -> String {
+        let titles: Vec<String> = (0..self.tabs.len())
+            .map(|i| {
+                let (path, modified) = if i == self.active_tab {
+                    (&self.rom_path, self.modified)
                 } else {
-                    print!(" {:02X}", self.data[i]);
-                }
-            }
-            
-            // Preencher espaços vazios
-            for _ in row_end..row_offset + BYTES_PER_ROW {
-                print!("   ");
-            }
-            
-            // ASCII
-            print!(" | ");
-            for i in row_offset..row_end {
-                let byte = self.data[i];
-                if byte >= 32 && byte <= 126 {
-                    print!("{}", byte as char);
+                    (&self.tabs[i].rom_path, self.tabs[i].modified)
+                };
+                let name = path
+                    .as_ref()
+                    .and_then(|path| path.file_name())
+                    .map_or("(sem nome)".to_string(), |name| name.to_string_lossy().into_owned());
+                let title = format!("{}: {}{}", i + 1, name, if modified { "*" } else { "" });
+                if i == self.active_tab {
+                    format!("[{}]", title)
                 } else {
-                    print!(".");
+                    title
                 }
-            }
-            println!();
-            
-            row_offset += BYTES_PER_ROW;
-            display_row += 1;
-        }
+            })
+            .collect();
+        format!("Abas: {}", titles.join(" | "))
     }
-}
 
-fn get_input(prompt: &str) -> String {
-    print!("{}", prompt);
-    io::stdout().flush().unwrap();
-    
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).expect("Falha ao ler entrada");
-    input.trim().to_string()
-}
+    // Arquivo de onde os dados vieram, exceto quando lidos da entrada padrão
+    fn disk_path(&self) -> Option<&PathBuf> {
+        self.rom_path.as_ref().filter(|path| path.as_os_str() != STDIN_PATH)
+    }
 
-fn main() {
-    let mut editor = HexEditor::new();
-    
-    // Verificar argumentos de linha de comando
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() > 1 {
-        match editor.open_file(&args[1]) {
-            Ok(_) => println!("Arquivo aberto: {}", args[1]),
-            Err(e) => println!("Erro ao abrir arquivo: {}", e),
+    // Os dados vieram da entrada padrão e só podem ser gravados com "salvar como"
+    fn is_stdin(&self) -> bool {
+        self.rom_path.is_some() && self.disk_path().is_none()
+    }
+
+    // Abrir um arquivo ROM ("-" lê da entrada padrão); em caso de erro o arquivo anterior continua carregado
+    fn open_file(&mut self, path: &Path) -> io::Result<()> {
+        let stdin = path.as_os_str() == STDIN_PATH;
+        // Diretórios são rejeitados antes da leitura, que com --mmap falharia com um erro genérico
+        if !stdin && path.is_dir() {
+            return Err(io::Error::new(io::ErrorKind::IsADirectory, "é um diretório"));
+        }
+        let data = if stdin {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            Buffer::from(bytes)
+        } else {
+            self.read_buffer(path)?
+        };
+        // Uma aba sem arquivo é reaproveitada; senão o arquivo abre em uma aba nova
+        if self.rom_path.is_some() || !self.data.is_empty() {
+            self.new_tab();
+        }
+        self.load_data(data);
+        self.rom_path = Some(path.to_path_buf());
+        self.view_offset = 0;
+        // Espaço livre em ROMs de GBA é 0xFF; nos demais arquivos, zeros
+        self.default_fill = if self.console() == Some(Console::Gba) { 0xFF } else { 0x00 };
+        // O cursor pode ter ficado além do fim de um arquivo menor
+        self.clamp_cursor();
+        self.load_bookmarks();
+        // A lista de recentes é só uma conveniência; falhar ao gravá-la não impede a abertura
+        if !stdin {
+            let _ = self.add_recent_file(path);
         }
+        Ok(())
     }
-    
-    // Configurar janela
-    let mut window = Window::new(
-        "Editor Hexadecimal para ROMs de Pokémon",
-        WIDTH * 10, HEIGHT * 20,  // Tamanho aproximado da janela
-        WindowOptions::default(),
-    )
-    .unwrap_or_else(|e| {
-        panic!("{}", e);
-    });
-    
-    // Limitar FPS para não consumir muita CPU
-    window.limit_update_rate(Some(std::time::Duration::from_millis(16)));
-    
-    // Loop principal
-    while window.is_open() && !window.is_key_down(Key::Q) {
-        // Entrada do teclado
-        if window.is_key_released(Key::O) {
-            // Abrir arquivo
-            let filename = get_input("Digite o caminho do arquivo para abrir: ");
-            if !filename.is_empty() {
-                match editor.open_file(&filename) {
-                    Ok(_) => println!("Arquivo aberto: {}", filename),
-                    Err(e) => println!("Erro ao abrir arquivo: {}", e),
-                }
-            }
+
+    // Abrir bytes gerados pelo editor (ex: um bloco descompactado) em uma aba sem arquivo
+    fn open_bytes(&mut self, bytes: Vec<u8>) {
+        if self.rom_path.is_some() || !self.data.is_empty() {
+            self.new_tab();
         }
-        
-        if window.is_key_released(Key::S) {
-            // Salvar arquivo
-            if editor.rom_path.is_none() {
-                let filename = get_input("Digite o caminho para salvar: ");
-                if !filename.is_empty() {
-                    editor.rom_path = Some(PathBuf::from(&filename));
-                }
-            }
-            
-            if let Err(e) = editor.save_file() {
-                println!("Erro ao salvar: {}", e);
-            }
+        self.load_data(Buffer::from(bytes));
+        self.view_offset = 0;
+        self.clamp_cursor();
+    }
+
+    // Dados de uma aba (a partir de 0), inclusive a ativa
+    fn tab_bytes(&self, index: usize) -> Option<Cow<'_, [u8]>> {
+        match index {
+            _ if index == self.active_tab => Some(self.data.bytes()),
+            _ => self.tabs.get(index).map(|doc| doc.data.bytes()),
         }
-        
-        // Movimentação do cursor
-        if window.is_key_released(Key::Up) {
-            editor.move_cursor(0, -1);
+    }
+
+    // Trocar o bloco LZ77 em `at` pela compactação de `input`, retornando os tamanhos (antigo, novo).
+    // Um bloco maior sobrescreve os bytes seguintes; cada byte tem a própria entrada de desfazer.
+    fn replace_lz77(&mut self, at: usize, input: &[u8]) -> io::Result<(usize, usize)> {
+        let old_len = lz77::compressed_len(&self.data.slice(at..self.data.len()), 0)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("não há um bloco LZ77 válido em 0x{:08X}", at)))?;
+        let compressed = lz77::compress(input)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "os dados a compactar estão vazios ou passam de 16 MB"))?;
+        if at + compressed.len() > self.data.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "o bloco compactado passaria do fim do arquivo"));
         }
-        if window.is_key_released(Key::Down) {
-            editor.move_cursor(0, 1);
+        if !self.writable() {
+            return Err(read_only_error());
         }
-        if window.is_key_released(Key::Left) {
-            editor.move_cursor(-3, 0);  // 3 caracteres por byte (2 dígitos + espaço)
+        // Um bloco escrito pela metade não descompacta, então um bloqueio no caminho recusa tudo
+        if self.overlaps_lock(at, at + compressed.len() - 1) {
+            return Err(locked_error());
         }
-        if window.is_key_released(Key::Right) {
-            editor.move_cursor(3, 0);
+        for (i, &byte) in compressed.iter().enumerate() {
+            self.edit_byte(at + i, byte);
         }
-        
-        // Rolagem
-        if window.is_key_released(Key::PageUp) {
-            editor.scroll(-10);
+        Ok((old_len, compressed.len()))
+    }
+
+    // Ler o arquivo para a memória ou, com --mmap ou --stream, deixá-lo no disco
+    fn read_buffer(&self, path: &Path) -> io::Result<Buffer> {
+        if self.use_stream {
+            Buffer::stream_file(path)
+        } else if self.use_mmap {
+            Buffer::map_file(path)
+        } else {
+            Ok(Buffer::from(fs::read(path)?))
         }
-        if window.is_key_released(Key::PageDown) {
-            editor.scroll(10);
+    }
+
+    // Ler os marcadores salvos ao lado da ROM (linhas "offset rótulo"); a ausência do arquivo não é erro
+    fn load_bookmarks(&mut self) {
+        self.bookmarks.clear();
+        let Some(path) = self.disk_path() else {
+            return;
+        };
+        let Ok(text) = fs::read_to_string(sibling_path(path, ".bookmarks")) else {
+            return;
+        };
+        for line in text.lines() {
+            let (offset, label) = line.split_once(' ').unwrap_or((line, ""));
+            if let Some(offset) = parse_offset(offset) {
+                self.bookmarks.push((offset, label.to_string()));
+            }
         }
-        
-        // Edição de bytes
-        if window.is_key_released(Key::Enter) {
-            if let Some(offset) = editor.get_cursor_offset() {
-                let current_value = editor.data[offset];
-                let input = get_input(&format!("Editar byte em 0x{:08X} [valor atual: 0x{:02X}]: 0x", offset, current_value));
-                
-                if !input.is_empty() {
-                    if let Ok(value) = u8::from_str_radix(&input, 16) {
-                        editor.edit_byte(offset, value);
-                        println!("Byte 0x{:08X} alterado para 0x{:02X}", offset, value);
-                    } else {
-                        println!("Valor inválido. Use formato hexadecimal (ex: 1F)");
-                    }
-                }
+        self.bookmarks.sort_by_key(|&(offset, _)| offset);
+    }
+
+    // Arquivo na pasta da configuração
+    fn config_path(&self, name: &str) -> Option<PathBuf> {
+        self.config_dir.as_ref().map(|dir| dir.join(name))
+    }
+
+    // Arquivo de configuração do tema (~/.pokehex_theme)
+    fn theme_path(&self) -> Option<PathBuf> {
+        self.config_path(".pokehex_theme")
+    }
+
+    // Terminadores e códigos de controle dos textos (~/.pokehex_text)
+    fn text_codes_path(&self) -> Option<PathBuf> {
+        self.config_path(".pokehex_text")
+    }
+
+    // Arquivo de configuração dos atalhos (~/.pokehex_keys)
+    fn keys_path(&self) -> Option<PathBuf> {
+        self.config_path(".pokehex_keys")
+    }
+
+    // Arquivo com os caminhos abertos recentemente (~/.pokehex_recent)
+    fn recent_files_path(&self) -> Option<PathBuf> {
+        self.config_path(".pokehex_recent")
+    }
+
+    // Ler os arquivos recentes, do mais recente para o mais antigo
+    fn load_recent_files(&self) -> Vec<PathBuf> {
+        self.recent_files_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| text.lines().filter(|line| !line.is_empty()).map(PathBuf::from).collect())
+            .unwrap_or_default()
+    }
+
+    // Colocar um caminho no topo da lista de recentes, sem duplicatas e limitada a MAX_RECENT_FILES
+    fn add_recent_file(&self, path: &Path) -> io::Result<()> {
+        let Some(recent_path) = self.recent_files_path() else {
+            return Ok(());
+        };
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let mut recent = self.load_recent_files();
+        recent.retain(|p| *p != path);
+        recent.insert(0, path);
+        recent.truncate(MAX_RECENT_FILES);
+
+        let text: String = recent.iter().map(|p| format!("{}\n", p.display())).collect();
+        fs::write(recent_path, text)
+    }
+
+    // Última sessão (~/.pokehex_session): arquivo aberto e posição
+    fn session_path(&self) -> Option<PathBuf> {
+        self.config_path(".pokehex_session")
+    }
+
+    // Gravar o arquivo da aba ativa e a posição nele, para retomar na próxima execução
+    fn save_session(&self) -> io::Result<()> {
+        let Some(session_path) = self.session_path() else {
+            return Ok(());
+        };
+        let Some(path) = self.disk_path() else {
+            // Sem arquivo aberto (ou com dados da entrada padrão) não há o que retomar
+            return match fs::remove_file(session_path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        };
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+        let cursor = self.get_cursor_offset().unwrap_or(self.view_offset);
+        let text = format!("arquivo={}\nvisao=0x{:08X}\ncursor=0x{:08X}\n", path.display(), self.view_offset, cursor);
+        fs::write(session_path, text)
+    }
+
+    // Reabrir o arquivo da última sessão onde ele ficou; sem sessão ou com o arquivo apagado, começa vazio
+    fn restore_session(&mut self) {
+        let Some(text) = self.session_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return;
+        };
+        let (mut path, mut view_offset, mut cursor) = (None, 0, 0);
+        for line in text.lines() {
+            match line.split_once('=') {
+                Some(("arquivo", value)) => path = Some(PathBuf::from(value)),
+                Some(("visao", value)) => view_offset = parse_offset(value).unwrap_or(0),
+                Some(("cursor", value)) => cursor = parse_offset(value).unwrap_or(0),
+                _ => {}
             }
         }
-        
-        // Renderizar a interface
-        editor.render();
-        
-        // Atualizar a janela
-        window.update();
+        let Some(path) = path.filter(|path| path.is_file()) else {
+            return;
+        };
+        match self.open_file(&path) {
+            Ok(_) => {
+                // O arquivo pode ter mudado de tamanho desde a última sessão
+                let view_offset = view_offset.min(self.max_view_offset());
+                self.view_offset = view_offset - view_offset % self.bytes_per_row;
+                self.goto_offset(cursor);
+                self.set_status(format!("Sessão retomada: {}", path.display()));
+            }
+            Err(e) => self.set_status(open_error_message(&path, &e)),
+        }
+    }
+
+    // Gravar os marcadores ao lado da ROM
+    fn save_bookmarks(&self) -> io::Result<()> {
+        let Some(path) = self.disk_path() else {
+            return Ok(());
+        };
+        let text: String = self
+            .bookmarks
+            .iter()
+            .map(|(offset, label)| format!("0x{:08X} {}\n", offset, label))
+            .collect();
+        fs::write(sibling_path(path, ".bookmarks"), text)
+    }
+
+    // Adicionar (ou renomear) um marcador no offset
+    fn add_bookmark(&mut self, offset: usize, label: &str) -> io::Result<()> {
+        match self.bookmarks.binary_search_by_key(&offset, |&(o, _)| o) {
+            Ok(i) => self.bookmarks[i].1 = label.to_string(),
+            Err(i) => self.bookmarks.insert(i, (offset, label.to_string())),
+        }
+        self.save_bookmarks()
+    }
+
+    // Ir para o marcador de número `index` (0 = o de menor offset)
+    fn goto_bookmark(&mut self, index: usize) -> Option<(usize, String)> {
+        let bookmark = self.bookmarks.get(index).cloned()?;
+        self.push_nav();
+        self.goto_offset(bookmark.0);
+        Some(bookmark)
+    }
+
+    // Ir para o próximo marcador depois do cursor, voltando ao primeiro no fim
+    fn next_bookmark(&mut self) -> Option<(usize, String)> {
+        let cursor = self.get_cursor_offset();
+        let next = self
+            .bookmarks
+            .iter()
+            .find(|&&(offset, _)| cursor.is_none_or(|cursor| offset > cursor))
+            .or_else(|| self.bookmarks.first())
+            .cloned()?;
+        self.push_nav();
+        self.goto_offset(next.0);
+        Some(next)
     }
-}
\ No newline at end of file
+
+    fn is_bookmarked(&self, offset: usize) -> bool {
+        self.bookmarks.binary_search_by_key(&offset, |&(o, _)| o).is_ok()
+    }
+
+    // Reler o arquivo do disco, descartando as alterações em memória
+    fn reload(&mut self) -> io::Result<()> {
+        if self.is_stdin() {
+            return Err(stdin_error());
+        }
+        let Some(path) = self.rom_path.clone() else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "nenhum arquivo aberto"));
+        };
+        let data = self.read_buffer(&path)?;
+        self.load_data(data);
+        self.clamp_view();
+        self.clamp_cursor();
+        Ok(())
+    }
+
+    // Substituir os dados e zerar o estado de edição associado a eles
+    fn load_data(&mut self, data: Buffer) {
+        self.original_len = data.len();
+        self.original = (!data.is_on_disk()).then(|| data.bytes().into_owned());
+        self.data = data;
+        self.data_changed();
+        self.modified = false;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.saved_depth = Some(0);
+        self.selection = None;
+        self.pending_nibble = None;
+        self.session_edits.clear();
+    }
+
+    // Carregar uma tabela de caracteres (.tbl), retornando quantas entradas foram lidas
+    fn load_table(&mut self, path: &str) -> io::Result<usize> {
+        let table = table::parse(&fs::read_to_string(path)?);
+        let count = table.len();
+        self.table = Some(table);
+        Ok(count)
+    }
+
+    // Texto a partir de `offset` até um dos terminadores (ou o fim dos dados) pela tabela carregada,
+    // e quantos bytes ele ocupa sem o terminador
+    fn decode_string(&self, offset: usize) -> (String, usize) {
+        let start = offset.min(self.data.len());
+        let len = (start..self.data.len())
+            .position(|i| self.text_codes.is_terminator(self.data[i]))
+            .unwrap_or(self.data.len() - start);
+        let bytes = self.data.slice(start..start + len);
+        let empty = HashMap::new();
+        (table::decode(self.table.as_ref().unwrap_or(&empty), &self.text_codes, &bytes), len)
+    }
+
+    // Bytes do texto pela tabela carregada, sem o terminador; None sem tabela ou com caracteres fora dela
+    fn encode_string(&self, text: &str) -> Option<Vec<u8>> {
+        table::encode(self.table.as_ref()?, &self.text_codes, text)
+    }
+
+    // Gravar o texto codificado e o primeiro terminador em `offset`, retornando quantos bytes foram escritos
+    fn write_string(&mut self, offset: usize, encoded: &[u8]) -> io::Result<usize> {
+        if self.read_only {
+            return Err(read_only_error());
+        }
+        let len = encoded.len() + 1;
+        if offset.saturating_add(len) > self.data.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "o texto passa do fim do arquivo"));
+        }
+        if self.overlaps_lock(offset, offset + len - 1) {
+            return Err(locked_error());
+        }
+        let terminator = self.text_codes.terminators[0];
+        for (i, &byte) in encoded.iter().chain([terminator].iter()).enumerate() {
+            self.edit_byte(offset + i, byte);
+        }
+        Ok(len)
+    }
+
+    // Aplicar um patch IPS aos dados, retornando quantos registros foram aplicados
+    fn apply_ips(&mut self, patch: &[u8]) -> io::Result<usize> {
+        if self.read_only {
+            return Err(read_only_error());
+        }
+        let patch = ips::parse(patch)?;
+        let original_len = self.data.len();
+        // Conferir os bloqueios antes de aplicar qualquer registro, para não deixar o patch pela metade
+        let touches_lock = patch.records.iter().any(|record| {
+            let (offset, len) = match record {
+                IpsRecord::Data { offset, bytes } => (*offset, bytes.len()),
+                IpsRecord::Rle { offset, len, .. } => (*offset, *len),
+            };
+            len > 0 && self.overlaps_lock(offset, offset + len - 1)
+        });
+        let truncates_lock = patch.truncate.is_some_and(|len| self.locked_ranges.iter().any(|&(_, end)| end >= len));
+        if touches_lock || truncates_lock {
+            return Err(locked_error());
+        }
+        let grows = patch.records.iter().any(|record| match record {
+            IpsRecord::Data { offset, bytes } => offset + bytes.len() > original_len,
+            IpsRecord::Rle { offset, len, .. } => offset + len > original_len,
+        });
+        if !self.data.can_resize() && (grows || patch.truncate.is_some_and(|len| len != original_len)) {
+            return Err(fixed_size_error());
+        }
+
+        for record in &patch.records {
+            let (offset, bytes) = match record {
+                IpsRecord::Data { offset, bytes } => (*offset, bytes.clone()),
+                IpsRecord::Rle { offset, len, value } => (*offset, vec![*value; *len]),
+            };
+            // Registros além do fim aumentam o arquivo
+            if offset + bytes.len() > self.data.len() {
+                self.data_vec().resize(offset + bytes.len(), 0x00);
+            }
+            for (i, &byte) in bytes.iter().enumerate() {
+                self.edit_byte(offset + i, byte);
+            }
+        }
+        if let Some(len) = patch.truncate {
+            self.data_vec().truncate(len);
+        }
+
+        if self.data.len() != original_len {
+            self.invalidate_history();
+            self.clamp_view();
+        }
+        self.modified = true;
+        Ok(patch.records.len())
+    }
+
+    // Gerar um patch IPS com as diferenças entre os dados em memória e o arquivo original no disco
+    fn create_ips(&self) -> io::Result<Vec<u8>> {
+        if self.is_stdin() {
+            return Err(stdin_error());
+        }
+        let Some(ref path) = self.rom_path else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "nenhum arquivo aberto"));
+        };
+        let original = fs::read(path)?;
+        ips::build(&original, &self.data.bytes())
+    }
+
+    // Carregar um arquivo de referência para comparação
+    fn load_reference(&mut self, path: &str) -> io::Result<usize> {
+        let reference = fs::read(path)?;
+        let len = reference.len();
+        self.reference = Some(reference);
+        Ok(len)
+    }
+
+    // O byte difere da referência (bytes além do fim da referência também contam)
+    fn differs(&self, offset: usize) -> bool {
+        match self.reference {
+            Some(ref reference) => reference.get(offset).copied() != self.data.get(offset),
+            None => false,
+        }
+    }
+
+    // Próximo offset a partir de `from` em que os dados diferem da referência
+    fn next_diff(&self, from: usize) -> Option<usize> {
+        self.reference.as_ref()?;
+        (from..self.data.len()).find(|&offset| self.differs(offset))
+    }
+
+    // Salvar as alterações no arquivo
+    fn save_file(&mut self) -> io::Result<()> {
+        if self.read_only {
+            return Err(read_only_error());
+        }
+        if self.is_stdin() {
+            return Err(stdin_error());
+        }
+        if let Some(ref path) = self.rom_path {
+            // Sem backup não há como recuperar o original, então a gravação é abortada.
+            // Um .bak que já existe é o original de antes da primeira gravação e não é sobrescrito.
+            let backup = sibling_path(path, ".bak");
+            if self.backup_on_save && path.exists() && !backup.exists() {
+                fs::copy(path, &backup).map_err(|e| {
+                    io::Error::new(e.kind(), format!("falha ao criar backup {}: {}", backup.display(), e))
+                })?;
+            }
+            self.data.save(path)?;
+            self.original = (!self.data.is_on_disk()).then(|| self.data.bytes().into_owned());
+            self.data_changed();
+            // Depois de salvo, a cópia de recuperação não tem mais nada a oferecer
+            let _ = fs::remove_file(sibling_path(path, ".recovery"));
+            let message = format!("Arquivo salvo: {}", path.display());
+            self.modified = false;
+            self.saved_depth = Some(self.undo_stack.len());
+            self.set_status(message);
+        }
+        Ok(())
+    }
+
+    // Salvar em um novo caminho, que passa a ser o destino dos próximos salvamentos
+    fn save_as(&mut self, path: &Path) -> io::Result<()> {
+        let previous = self.rom_path.replace(path.to_path_buf());
+        if let Err(e) = self.save_file() {
+            self.rom_path = previous;
+            return Err(e);
+        }
+        if !self.bookmarks.is_empty() {
+            self.save_bookmarks()?;
+        }
+        Ok(())
+    }
+
+    // Abrir uma pergunta no rodapé; a resposta chega a `finish_prompt` quando Enter for pressionado
+    fn start_prompt(&mut self, label: impl Into<String>, action: PromptAction) {
+        self.prompt = Some(Prompt { label: label.into(), text: String::new(), action });
+    }
+
+    // Pergunta do "ir para", conforme a base dos endereços
+    fn goto_prompt(&self) -> String {
+        let base = match self.address_base {
+            AddressBase::Hex => "hex",
+            AddressBase::Decimal => "decimal ou 0x hex",
+        };
+        if self.base_offset.is_some() {
+            format!("Ir para o offset ({}, +X/-X relativo à base, ou N%): ", base)
+        } else {
+            format!("Ir para o offset ({}, ou N%): ", base)
+        }
+    }
+
+    // Salvar depois das confirmações; "salvar como" pergunta o caminho
+    fn finish_save(&mut self, save_as: bool) {
+        if !save_as {
+            if let Err(e) = self.save_file() {
+                self.set_status(format!("Erro ao salvar: {}", e));
+            }
+            return;
+        }
+        match dialog_save_path() {
+            Some(path) => self.finish_prompt(PromptAction::SaveAs, &path.to_string_lossy()),
+            None => self.start_prompt("Salvar como: ", PromptAction::SaveAs),
+        }
+    }
+
+    // Mostrar a tabela de registros recém-definida
+    fn report_records(&mut self) {
+        if let Some(size) = self.struct_size {
+            let index = self.step_record(0);
+            self.set_status(format!(
+                "Registros de {} bytes a partir de 0x{:08X}{}",
+                size,
+                self.struct_base,
+                index.map_or(String::new(), |index| format!(" (registro {})", index))
+            ));
+        }
+    }
+
+    // Executar o comando que abriu a pergunta com o texto digitado; resposta vazia não faz nada,
+    // salvo nas perguntas que a aceitam
+    fn finish_prompt(&mut self, action: PromptAction, input: &str) {
+        let input = input.trim();
+        if input.is_empty() && !action.keeps_empty() {
+            return;
+        }
+        match action {
+            PromptAction::Open => {
+                let path = PathBuf::from(input);
+                match self.open_file(&path) {
+                    Ok(_) => self.set_status(format!("Arquivo aberto: {}", path.display())),
+                    Err(e) => self.set_status(open_error_message(&path, &e)),
+                }
+            }
+            PromptAction::SaveAs => {
+                if let Err(e) = self.save_as(Path::new(input)) {
+                    self.set_status(format!("Erro ao salvar: {}", e));
+                }
+            }
+            PromptAction::Goto => {
+                // A posição só entra no histórico quando o destino é válido
+                if let Some(pct) = input.strip_suffix('%') {
+                    // "50%" vai para a metade do arquivo
+                    match pct.trim().parse::<f32>() {
+                        Ok(pct) => {
+                            self.push_nav();
+                            self.goto_percent(pct);
+                        }
+                        Err(_) => self.set_status("Porcentagem inválida (ex: 50%)"),
+                    }
+                } else {
+                    match self.resolve_offset(input) {
+                        Some(offset) => {
+                            self.push_nav();
+                            self.goto_offset(offset);
+                        }
+                        None if self.address_base == AddressBase::Decimal => {
+                            self.set_status("Offset inválido. Use formato decimal (ex: 7978) ou hexadecimal com 0x (ex: 0x1F2A)")
+                        }
+                        None => self.set_status("Offset inválido. Use formato hexadecimal (ex: 0x1F2A ou 1F2A)"),
+                    }
+                }
+            }
+            PromptAction::SearchBytes => match parse_search_pattern(input) {
+                Some(pattern) => {
+                    self.last_search = Some(SearchPattern::Bytes(pattern));
+                    match self.find_from(0) {
+                        Some(offset) => self.set_status(format!("Padrão encontrado em 0x{:08X}", offset)),
+                        None => self.set_status("Padrão não encontrado"),
+                    }
+                }
+                None => self.set_status("Padrão inválido. Use bytes hexadecimais ou ?? separados por espaço"),
+            },
+            PromptAction::SearchText => {
+                self.last_search = Some(SearchPattern::Text(input.to_string()));
+                match self.find_from(0) {
+                    Some(offset) => self.set_status(format!("Texto encontrado em 0x{:08X}", offset)),
+                    None => self.set_status("Texto não encontrado"),
+                }
+            }
+            PromptAction::Recovery(recovery) => {
+                if input.eq_ignore_ascii_case("s") {
+                    match self.restore_recovery(&recovery) {
+                        Ok(()) => self.set_status("Cópia de recuperação restaurada; salve para mantê-la"),
+                        Err(e) => self.set_status(format!("Erro ao restaurar a recuperação: {}", e)),
+                    }
+                } else {
+                    let _ = fs::remove_file(&recovery);
+                }
+            }
+            PromptAction::Quit => match input.to_lowercase().as_str() {
+                "s" => match self.save_all() {
+                    Ok(_) => self.quitting = true,
+                    Err(e) => self.set_status(format!("Erro ao salvar: {}", e)),
+                },
+                "d" => self.quitting = true,
+                _ => self.set_status("Saída cancelada"),
+            },
+            PromptAction::CloseTab => {
+                let close = match input.to_lowercase().as_str() {
+                    "s" => match self.save_file() {
+                        Ok(_) => true,
+                        Err(e) => {
+                            self.set_status(format!("Erro ao salvar: {}", e));
+                            false
+                        }
+                    },
+                    "d" => true,
+                    _ => false,
+                };
+                if close {
+                    self.close_tab();
+                }
+            }
+            PromptAction::RomSize { save_as } => {
+                let confirmed = match input.to_lowercase().as_str() {
+                    // Sem poder mudar o tamanho (--stream), o salvamento é cancelado com o aviso no rodapé
+                    "p" => self.resizable() && {
+                        self.pad_to_power_of_two();
+                        true
+                    },
+                    "s" => true,
+                    "i" => {
+                        self.warn_rom_size = false;
+                        true
+                    }
+                    _ => false,
+                };
+                if confirmed {
+                    self.finish_save(save_as);
+                }
+            }
+            PromptAction::Reload => {
+                if input.eq_ignore_ascii_case("s") {
+                    match self.reload() {
+                        Ok(_) => self.set_status("Arquivo recarregado"),
+                        Err(e) => self.set_status(format!("Erro ao recarregar: {}", e)),
+                    }
+                }
+            }
+            PromptAction::Reference => match self.load_reference(input) {
+                Ok(len) => self.set_status(format!("Referência carregada: {} ({} bytes)", input, len)),
+                Err(e) => self.set_status(format!("Erro ao abrir referência: {}", e)),
+            },
+            PromptAction::Snapshot => {
+                let replaced = self.snapshots.contains_key(input);
+                self.take_snapshot(input);
+                self.set_status(format!(
+                    "Instantâneo \"{}\" {} ({} bytes)",
+                    input,
+                    if replaced { "substituído" } else { "guardado" },
+                    self.data.len()
+                ));
+            }
+            PromptAction::RestoreSnapshot => match self.restore_snapshot(input) {
+                Ok(()) => self.set_status(format!("Instantâneo \"{}\" restaurado", input)),
+                Err(e) => self.set_status(format!("Não foi possível restaurar: {}", e)),
+            },
+            PromptAction::DumpScope => {
+                let range = match input.to_lowercase().as_str() {
+                    "v" => Some(self.visible_range()),
+                    "t" => Some(None),
+                    _ => None,
+                };
+                match range {
+                    Some(range) => self.start_prompt("Digite o caminho do arquivo .txt: ", PromptAction::DumpPath(range)),
+                    None => self.set_status("Exportação cancelada"),
+                }
+            }
+            PromptAction::DumpPath(range) => match fs::write(input, self.export_hexdump(range)) {
+                Ok(_) => self.set_status(format!("Dump exportado: {}", input)),
+                Err(e) => self.set_status(format!("Erro ao exportar: {}", e)),
+            },
+            PromptAction::CArrayRange => match parse_range(input) {
+                Some((start, end)) => self.start_prompt("Nome do array: ", PromptAction::CArrayName(start, end)),
+                None => self.set_status("Intervalo inválido"),
+            },
+            PromptAction::CArrayName(start, end) => {
+                let name = if input.is_empty() { "data".to_string() } else { input.to_string() };
+                self.start_prompt("Digite o caminho do arquivo .h/.c: ", PromptAction::CArrayPath(start, end, name));
+            }
+            PromptAction::CArrayPath(start, end, name) => match fs::write(input, self.to_c_array(start, end, &name)) {
+                Ok(_) => self.set_status(format!("Array C exportado: {}", input)),
+                Err(e) => self.set_status(format!("Erro ao exportar: {}", e)),
+            },
+            PromptAction::ApplyIps => match fs::read(input).and_then(|patch| self.apply_ips(&patch)) {
+                Ok(count) => self.set_status(format!("Patch aplicado: {} registros", count)),
+                Err(e) => self.set_status(format!("Erro ao aplicar patch: {}", e)),
+            },
+            PromptAction::CreateIps => match self.create_ips().and_then(|patch| fs::write(input, patch)) {
+                Ok(_) => self.set_status(format!("Patch gerado: {}", input)),
+                Err(e) => self.set_status(format!("Erro ao gerar patch: {}", e)),
+            },
+            PromptAction::Recent => {
+                let recent = self.load_recent_files();
+                match input.parse::<usize>().ok().and_then(|n| recent.get(n.wrapping_sub(1))) {
+                    Some(path) => match self.open_file(path) {
+                        Ok(_) => self.set_status(format!("Arquivo aberto: {}", path.display())),
+                        Err(e) => self.set_status(open_error_message(path, &e)),
+                    },
+                    None => self.set_status("Número inválido"),
+                }
+            }
+            PromptAction::Table => match self.load_table(input) {
+                Ok(count) => self.set_status(format!("Tabela carregada: {} caracteres", count)),
+                Err(e) => self.set_status(format!("Erro ao carregar tabela: {}", e)),
+            },
+            PromptAction::ReplaceFind => match parse_hex_bytes(input) {
+                Some(find) if !find.is_empty() => {
+                    self.start_prompt("Substituir por (hex, mesmo tamanho): ", PromptAction::ReplaceWith(find))
+                }
+                _ => self.set_status("Padrão inválido. Use bytes hexadecimais separados por espaço"),
+            },
+            PromptAction::ReplaceWith(find) => match parse_hex_bytes(input) {
+                Some(replace) if replace.len() == find.len() => {
+                    let found = self.find_all(&find);
+                    if found.is_empty() {
+                        self.set_status("Padrão não encontrado");
+                    } else {
+                        let prompt = format!(
+                            "{} ocorrências (primeira em 0x{:08X}). Substituir todas? (s/N): ",
+                            found.len(),
+                            found[0]
+                        );
+                        self.start_prompt(prompt, PromptAction::ReplaceConfirm(find, replace));
+                    }
+                }
+                Some(_) => self.set_status("Os padrões precisam ter o mesmo tamanho"),
+                None => self.set_status("Padrão inválido. Use bytes hexadecimais separados por espaço"),
+            },
+            PromptAction::ReplaceConfirm(find, replace) => {
+                if input.eq_ignore_ascii_case("s") {
+                    let total = self.find_all(&find).len();
+                    let count = self.replace_all(&find, &replace);
+                    match total - count {
+                        0 => self.set_status(format!("{} ocorrências substituídas", count)),
+                        skipped => self.set_status(format!(
+                            "{} ocorrências substituídas; {} em trechos bloqueados ficaram como estavam",
+                            count, skipped
+                        )),
+                    }
+                } else {
+                    self.set_status("Substituição cancelada");
+                }
+            }
+            PromptAction::Count => match parse_hex_bytes(input) {
+                Some(pattern) if !pattern.is_empty() => {
+                    let count = match pattern[..] {
+                        [value] => self.count_occurrences(value),
+                        _ => self.find_all(&pattern).len(),
+                    };
+                    // Só as primeiras ocorrências, sem percorrer o arquivo de novo
+                    let mut first = Vec::new();
+                    let mut start = 0;
+                    while first.len() < 5 {
+                        let Some(offset) = self.search(&pattern, start) else { break };
+                        first.push(format!("0x{:08X}", offset));
+                        start = offset + pattern.len();
+                    }
+                    let mut message = format!("{} ocorrências", count);
+                    if !first.is_empty() {
+                        message += &format!(": {}", first.join(", "));
+                        if count > first.len() {
+                            message += ", ...";
+                        }
+                    }
+                    self.set_status(message);
+                }
+                _ => self.set_status("Padrão inválido. Use bytes hexadecimais separados por espaço"),
+            },
+            PromptAction::Resize => match input.to_lowercase().as_str() {
+                "p" => match self.pad_to_power_of_two() {
+                    0 => self.set_status("O tamanho já é uma potência de 2"),
+                    added => self.set_status(format!("{} bytes 0x{:02X} adicionados ({} bytes)", added, self.default_fill, self.data.len())),
+                },
+                "t" => match self.truncate_to_original() {
+                    0 => self.set_status("O arquivo não é maior que o original"),
+                    removed => self.set_status(format!("{} bytes removidos ({} bytes)", removed, self.data.len())),
+                },
+                "e" => {
+                    let prompt = format!("Tamanho em MB (ex: 8, 16 ou 32) [byte 0x{:02X}, ex: 16 00]: ", self.default_fill);
+                    self.start_prompt(prompt, PromptAction::ExpandSize);
+                }
+                "a" => {
+                    let prompt = format!("Byte de preenchimento a remover do fim (hex) [{:02X}]: ", self.default_fill);
+                    self.start_prompt(prompt, PromptAction::TrimByte);
+                }
+                _ => {}
+            },
+            PromptAction::ExpandSize => {
+                // Tamanho em MB e, opcionalmente, o byte de preenchimento
+                let mut parts = input.split_whitespace();
+                let size = parts.next().and_then(|mb| mb.parse::<usize>().ok()).and_then(|mb| mb.checked_mul(1024 * 1024));
+                let fill = parts.next().map_or(Some(self.default_fill), |byte| u8::from_str_radix(byte, 16).ok());
+                match (size, fill) {
+                    (Some(size), Some(_)) if size <= self.data.len() => {
+                        self.set_status(format!("O arquivo já tem {} bytes", self.data.len()))
+                    }
+                    (Some(size), Some(fill)) => {
+                        self.pad_to(size, fill);
+                        self.set_status(format!("Arquivo expandido para {} bytes", self.data.len()));
+                    }
+                    _ => self.set_status("Entrada inválida. Use o tamanho em MB e, opcionalmente, o byte em hex"),
+                }
+            }
+            PromptAction::TrimByte => {
+                let fill = if input.is_empty() { Some(self.default_fill) } else { u8::from_str_radix(input, 16).ok() };
+                match fill {
+                    // Os bytes finais podem ser dados de verdade, então a remoção é confirmada
+                    Some(fill) => {
+                        let prompt = format!("Remover os bytes 0x{:02X} do fim do arquivo? (s/N): ", fill);
+                        self.start_prompt(prompt, PromptAction::TrimConfirm(fill));
+                    }
+                    None => self.set_status("Byte inválido"),
+                }
+            }
+            PromptAction::TrimConfirm(fill) => {
+                if input.eq_ignore_ascii_case("s") {
+                    match self.trim_trailing(fill) {
+                        0 => self.set_status(format!("O arquivo não termina com 0x{:02X}", fill)),
+                        removed => self.set_status(format!("{} bytes removidos ({} bytes)", removed, self.data.len())),
+                    }
+                }
+            }
+            PromptAction::Bookmark(offset) => match self.add_bookmark(offset, input) {
+                Ok(_) => self.set_status(format!("Marcador adicionado em 0x{:08X}", offset)),
+                Err(e) => self.set_status(format!("Erro ao salvar marcadores: {}", e)),
+            },
+            PromptAction::Terminators => match parse_hex_bytes(input) {
+                Some(bytes) if !bytes.is_empty() => {
+                    self.text_codes.terminators = bytes;
+                    self.set_status(format!("Terminadores dos textos: {}", self.text_codes.terminators_label()));
+                }
+                _ => self.set_status("Bytes inválidos"),
+            },
+            PromptAction::KnownOffset => {
+                let known = self.detect_game().map(|game| game.known_offsets()).unwrap_or_default();
+                match input.parse::<usize>().ok().and_then(|n| known.get(n.wrapping_sub(1))) {
+                    Some(&(label, offset)) => {
+                        self.push_nav();
+                        self.goto_offset_top(offset);
+                        self.set_status(format!("{} em 0x{:08X}", label, offset));
+                    }
+                    None => self.set_status("Opção inválida"),
+                }
+            }
+            PromptAction::WriteInt(offset) => {
+                // "valor largura" também muda a largura (1, 2 ou 4 bytes)
+                let endian = self.endianness;
+                let mut parts = input.split_whitespace();
+                let value = parts.next().map(str::parse::<u64>);
+                let width = parts.next().map_or(Ok(self.int_width), str::parse::<usize>);
+                match (value, width) {
+                    (None, _) => {}
+                    (Some(Ok(value)), Ok(width)) => match self.write_int(offset, width, value, endian) {
+                        Ok(()) => {
+                            self.int_width = width;
+                            self.set_status(format!("{} escrito em {} bytes em 0x{:08X}", value, width, offset));
+                        }
+                        Err(e) => self.set_status(format!("Valor não escrito: {}", e)),
+                    },
+                    _ => self.set_status("Entrada inválida. Use o valor em decimal e, opcionalmente, a largura (ex: 500 ou 500 4)"),
+                }
+            }
+            PromptAction::WritePointer(offset) => match parse_offset(input) {
+                Some(target) => match self.write_pointer(offset, target) {
+                    Ok(()) => self.set_status(format!(
+                        "Ponteiro 0x{:08X} escrito em 0x{:08X}",
+                        GBA_ROM_BASE + target,
+                        offset
+                    )),
+                    Err(e) => self.set_status(format!("Ponteiro não escrito: {}", e)),
+                },
+                None => self.set_status("Offset inválido. Use formato hexadecimal (ex: 0x1F2A ou 1F2A)"),
+            },
+            PromptAction::FreeSpace => {
+                let mut parts = input.split_whitespace();
+                let min_len = parts.next().and_then(|len| len.parse::<usize>().ok());
+                let free_byte = parts.next().map(|byte| u8::from_str_radix(byte, 16).ok());
+                match (min_len, free_byte) {
+                    (Some(min_len), None | Some(Some(_))) if min_len > 0 => {
+                        if let Some(Some(byte)) = free_byte {
+                            self.free_byte = byte;
+                        }
+                        let start = self.get_cursor_offset().map_or(self.view_offset, |offset| offset + 1);
+                        match self.find_free_space(min_len, start) {
+                            Some((offset, len)) => {
+                                self.push_nav();
+                                self.goto_offset(offset);
+                                self.set_status(format!("Espaço livre em 0x{:08X}: {} bytes de 0x{:02X}", offset, len, self.free_byte));
+                            }
+                            None => self.set_status(format!("Nenhuma sequência de {} bytes 0x{:02X} depois do cursor", min_len, self.free_byte)),
+                        }
+                    }
+                    _ => self.set_status("Entrada inválida. Use o tamanho em decimal e, opcionalmente, o byte em hex"),
+                }
+            }
+            PromptAction::EditByte(offset) => match u8::from_str_radix(input, 16) {
+                Ok(value) => {
+                    self.edit_byte(offset, value);
+                    self.set_status(format!("Byte 0x{:08X} alterado para 0x{:02X}", offset, value));
+                }
+                Err(_) => self.set_status("Valor inválido. Use formato hexadecimal (ex: 1F)"),
+            },
+            PromptAction::Fill(start, end) => match u8::from_str_radix(input, 16) {
+                Ok(value) => {
+                    if self.fill_range(start, end, value) {
+                        self.set_status(format!("{} bytes preenchidos com 0x{:02X}", end - start + 1, value));
+                    }
+                }
+                Err(_) => self.set_status("Valor inválido. Use formato hexadecimal (ex: FF)"),
+            },
+            PromptAction::Stride => match input.parse::<usize>() {
+                Ok(stride) if stride > 0 => {
+                    self.stride = stride;
+                    self.set_status(format!("Passo: {} bytes", stride));
+                }
+                _ => self.set_status("Passo inválido. Use um número decimal maior que zero"),
+            },
+            PromptAction::RecordSize => {
+                // "tamanho [início em hex]"
+                let mut parts = input.split_whitespace();
+                let size = parts.next().map(str::parse::<usize>);
+                let base = parts.next().map(parse_offset);
+                match (size, base) {
+                    (None, _) => {}
+                    (Some(Ok(0)), None) => {
+                        self.struct_size = None;
+                        self.set_status("Navegação por registros desativada");
+                    }
+                    (Some(Ok(size)), None) => {
+                        self.struct_size = Some(size);
+                        self.struct_base = 0;
+                        self.report_records();
+                    }
+                    (Some(Ok(size)), Some(Some(base))) if size > 0 => {
+                        self.struct_size = Some(size);
+                        self.struct_base = base;
+                        self.report_records();
+                    }
+                    _ => self.set_status("Entrada inválida. Use o tamanho em decimal e, opcionalmente, o início em hex"),
+                }
+            }
+            PromptAction::Bitwise(start, end) => {
+                let op = match input.to_ascii_uppercase().as_str() {
+                    "A" => Some(BitOp::And),
+                    "O" => Some(BitOp::Or),
+                    "X" => Some(BitOp::Xor),
+                    "N" => Some(BitOp::Not),
+                    _ => None,
+                };
+                match op {
+                    // NOT não usa máscara
+                    Some(BitOp::Not) => self.finish_prompt(PromptAction::BitMask(start, end, BitOp::Not), "0"),
+                    Some(op) => self.start_prompt("Máscara: 0x", PromptAction::BitMask(start, end, op)),
+                    None => self.set_status("Operação inválida"),
+                }
+            }
+            PromptAction::BitMask(start, end, op) => match u8::from_str_radix(input, 16) {
+                Ok(mask) => {
+                    if self.apply_bitwise((start, end), op, mask) {
+                        self.set_status(format!("Operação aplicada em {} bytes", end - start + 1));
+                    }
+                }
+                Err(_) => self.set_status("Máscara inválida. Use formato hexadecimal (ex: 0F)"),
+            },
+            PromptAction::DefaultFill => match u8::from_str_radix(input, 16) {
+                Ok(fill) => {
+                    self.default_fill = fill;
+                    self.set_status(format!("Byte de preenchimento: 0x{:02X}", fill));
+                }
+                Err(_) => self.set_status("Valor inválido. Use formato hexadecimal (ex: FF)"),
+            },
+            PromptAction::Template if input.is_empty() => {
+                if self.template.take().is_some() {
+                    self.set_status("Modelo removido");
+                }
+            }
+            PromptAction::Template => match fs::read_to_string(input).and_then(|text| template::parse(&text)) {
+                Ok(template) => match template.offset.or(self.get_cursor_offset()) {
+                    Some(base) => {
+                        self.set_status(format!(
+                            "Modelo {} aplicado em 0x{:08X}: {} campos",
+                            template.name,
+                            base,
+                            template.fields.len()
+                        ));
+                        self.template = Some((template, base));
+                    }
+                    None => self.set_status("Nenhum dado para aplicar o modelo"),
+                },
+                Err(e) => self.set_status(format!("Erro no modelo: {}", e)),
+            },
+            PromptAction::TemplateField(offset) => {
+                if let Some(field) = self.template_field_at(offset) {
+                    match self.write_field(&field, input) {
+                        Ok(()) => self.set_status(format!("Campo {} escrito em 0x{:08X}", field.name, field.offset)),
+                        Err(e) => self.set_status(format!("Campo não escrito: {}", e)),
+                    }
+                }
+            }
+            PromptAction::Script => match fs::read_to_string(input).and_then(|text| self.run_script(&text)) {
+                Ok(count) => self.set_status(format!("Roteiro aplicado: {} comandos", count)),
+                Err(e) => self.set_status(format!("Erro no roteiro: {}", e)),
+            },
+            PromptAction::Lz77(offset, bytes) => {
+                let len = bytes.len();
+                match input.to_lowercase().as_str() {
+                    "a" => {
+                        self.open_bytes(bytes);
+                        self.set_status(format!("{} bytes descompactados de 0x{:08X} abertos em nova aba", len, offset));
+                    }
+                    "s" => self.start_prompt("Salvar os dados descompactados em: ", PromptAction::Lz77Save(bytes)),
+                    // Fecha o ciclo descompactar-editar-compactar: a origem costuma ser a aba aberta com (A)
+                    "c" => self.start_prompt("Dados a compactar (número da aba ou caminho do arquivo): ", PromptAction::Lz77Source(offset)),
+                    _ => self.set_status(format!("LZ77 em 0x{:08X}: {} bytes descompactados", offset, len)),
+                }
+            }
+            PromptAction::Lz77Save(bytes) => match fs::write(input, &bytes) {
+                Ok(()) => self.set_status(format!("{} bytes salvos em {}", bytes.len(), input)),
+                Err(e) => self.set_status(format!("Erro ao salvar: {}", e)),
+            },
+            PromptAction::Lz77Source(offset) => {
+                let source = match input.parse::<usize>() {
+                    Ok(tab) => self
+                        .tab_bytes(tab.wrapping_sub(1))
+                        .map(Cow::into_owned)
+                        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("aba {} não existe", tab))),
+                    Err(_) if input.is_empty() => Err(io::Error::new(io::ErrorKind::InvalidInput, "nenhuma origem informada")),
+                    Err(_) => fs::read(input),
+                };
+                match source {
+                    Ok(source) => {
+                        let old_len = lz77::compressed_len(&self.data.slice(offset..self.data.len()), 0).unwrap_or(0);
+                        // Sem bloco compactado, replace_lz77 explica o motivo no rodapé
+                        let new_len = lz77::compress(&source).map_or(0, |block| block.len());
+                        if new_len <= old_len {
+                            self.finish_prompt(PromptAction::Lz77Confirm(offset, source), "s");
+                        } else {
+                            let prompt = format!(
+                                "O bloco novo tem {} bytes, {} a mais que o original, e sobrescreve os dados seguintes. Continuar? (s/N): ",
+                                new_len,
+                                new_len - old_len
+                            );
+                            self.start_prompt(prompt, PromptAction::Lz77Confirm(offset, source));
+                        }
+                    }
+                    Err(e) => self.set_status(format!("Não foi possível compactar: {}", e)),
+                }
+            }
+            PromptAction::Lz77Confirm(offset, source) => {
+                if !input.eq_ignore_ascii_case("s") {
+                    self.set_status("Compactação cancelada");
+                    return;
+                }
+                match self.replace_lz77(offset, &source) {
+                    Ok((old_len, new_len)) => self.set_status(format!(
+                        "Bloco LZ77 em 0x{:08X} trocado: {} -> {} bytes ({:+}){}",
+                        offset,
+                        old_len,
+                        new_len,
+                        new_len as isize - old_len as isize,
+                        if new_len > old_len { "; pode ser preciso reapontar para um espaço livre" } else { "" }
+                    )),
+                    Err(e) => self.set_status(format!("Não foi possível compactar: {}", e)),
+                }
+            }
+            PromptAction::ExportRange => match parse_range(input) {
+                Some((start, end)) => self.start_prompt("Digite o caminho do arquivo .bin: ", PromptAction::ExportBin(start, end)),
+                None => self.set_status("Intervalo inválido"),
+            },
+            PromptAction::ExportBin(start, end) => match self.export_range(start, end, input) {
+                Ok(len) => self.set_status(format!("{} bytes exportados: {}", len, input)),
+                Err(e) => self.set_status(format!("Erro ao exportar: {}", e)),
+            },
+            PromptAction::Import(offset) => match fs::read(input) {
+                Ok(bytes) => {
+                    let overflow = (offset + bytes.len()).saturating_sub(self.data.len());
+                    let prompt = format!(
+                        "O arquivo tem {} bytes e passa {} do fim dos dados. Importar só os que cabem? (s/N): ",
+                        bytes.len(),
+                        overflow
+                    );
+                    let action = PromptAction::ImportConfirm(offset, bytes, input.to_string());
+                    if self.edit_mode == EditMode::Insert || overflow == 0 {
+                        self.finish_prompt(action, "s");
+                    } else {
+                        self.start_prompt(prompt, action);
+                    }
+                }
+                Err(e) => self.set_status(format!("Erro ao importar: {}", e)),
+            },
+            PromptAction::ImportConfirm(offset, bytes, filename) => {
+                if input.eq_ignore_ascii_case("s") {
+                    let count = self.import_over(offset, &bytes);
+                    if count > 0 {
+                        self.set_status(format!("{} bytes importados em 0x{:08X}: {}", count, offset, filename));
+                    }
+                } else {
+                    self.set_status("Importação cancelada");
+                }
+            }
+            PromptAction::EditText(offset, old_len) => match self.encode_string(input) {
+                None => self.set_status("Texto com caracteres fora da tabela (use [XX] para bytes)"),
+                // Um texto mais longo sobrescreve o que vem depois do terminador original
+                Some(encoded) if encoded.len() > old_len => {
+                    let prompt = format!(
+                        "O texto novo tem {} bytes, {} a mais que o original, e sobrescreve os dados seguintes. Continuar? (s/N): ",
+                        encoded.len(),
+                        encoded.len() - old_len
+                    );
+                    self.start_prompt(prompt, PromptAction::TextConfirm(offset, encoded));
+                }
+                Some(encoded) => self.finish_prompt(PromptAction::TextConfirm(offset, encoded), "s"),
+            },
+            PromptAction::TextConfirm(offset, encoded) => {
+                if input.eq_ignore_ascii_case("s") {
+                    match self.write_string(offset, &encoded) {
+                        Ok(len) => self.set_status(format!("Texto gravado em 0x{:08X}: {} bytes", offset, len)),
+                        Err(e) => self.set_status(format!("Erro ao gravar texto: {}", e)),
+                    }
+                } else {
+                    self.set_status("Edição do texto cancelada");
+                }
+            }
+        }
+    }
+
+    // Última linha da tela: a pergunta em digitação ou a mensagem de status
+    fn footer_line(&self) -> String {
+        match self.prompt {
+            Some(ref prompt) => format!("{}{}{}", prompt.label, prompt.text, Theme::paint("7", " ")),
+            None => self.status_line().unwrap_or("").to_string(),
+        }
+    }
+
+    // Mostrar uma mensagem no rodapé, substituindo a anterior
+    fn set_status(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), Instant::now()));
+        self.dirty = true;
+    }
+
+    // Desenhar a tela só quando algo mudou desde o último desenho
+    fn redraw(&mut self) {
+        if self.dirty {
+            self.render();
+            self.dirty = false;
+        }
+    }
+
+    // Mensagem do rodapé, enquanto não expirar
+    fn status_line(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, since)| since.elapsed() < STATUS_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    // Verificar se edições são permitidas, avisando quando o modo somente leitura as bloqueia
+    fn writable(&mut self) -> bool {
+        if self.read_only {
+            self.set_status("Modo somente leitura: alteração ignorada");
+        }
+        !self.read_only
+    }
+
+    // Verificar se o tamanho dos dados pode mudar, avisando quando o arquivo é lido aos poucos (--stream)
+    fn resizable(&mut self) -> bool {
+        let resizable = self.data.can_resize();
+        if !resizable {
+            self.set_status("Arquivo aberto com --stream: o tamanho não pode mudar (inserções e remoções ignoradas)");
+        }
+        resizable
+    }
+
+    // Verificar se o intervalo (inclusivo) está livre de bloqueios, avisando quando não estiver
+    fn range_unlocked(&mut self, start: usize, end: usize) -> bool {
+        let locked = self.overlaps_lock(start, end);
+        if locked {
+            self.set_status("Intervalo bloqueado: alteração ignorada (Ctrl+Shift+L desbloqueia)");
+        }
+        !locked
+    }
+
+    // Algum byte do intervalo (inclusivo) está bloqueado, sem avisar no rodapé
+    fn overlaps_lock(&self, start: usize, end: usize) -> bool {
+        self.locked_ranges.iter().any(|&(a, b)| a <= end && start <= b)
+    }
+
+    // Trocar todos os dados por `new` mudaria algum byte bloqueado (ou o cortaria do fim)
+    fn replacement_touches_lock(&self, new: &[u8]) -> bool {
+        self.locked_ranges
+            .iter()
+            .any(|&(a, b)| new.get(a..=b).is_none_or(|bytes| self.data.get_slice(a..b + 1).is_none_or(|old| *old != *bytes)))
+    }
+
+    fn is_locked(&self, offset: usize) -> bool {
+        self.locked_ranges.iter().any(|&(start, end)| (start..=end).contains(&offset))
+    }
+
+    // Bloquear o intervalo (inclusivo), juntando-o aos bloqueios que ele toca
+    fn lock_range(&mut self, start: usize, end: usize) {
+        let (mut start, mut end) = (start.min(end), start.max(end));
+        self.locked_ranges.retain(|&(a, b)| {
+            let touches = a <= end.saturating_add(1) && start <= b.saturating_add(1);
+            if touches {
+                start = start.min(a);
+                end = end.max(b);
+            }
+            !touches
+        });
+        self.locked_ranges.push((start, end));
+        self.locked_ranges.sort_unstable();
+    }
+
+    // Desbloquear o intervalo (inclusivo), cortando os bloqueios que passam das suas bordas
+    fn unlock_range(&mut self, start: usize, end: usize) {
+        let (start, end) = (start.min(end), start.max(end));
+        self.locked_ranges = self
+            .locked_ranges
+            .iter()
+            .flat_map(|&(a, b)| {
+                if b < start || a > end {
+                    return vec![(a, b)];
+                }
+                let mut kept = Vec::new();
+                if a < start {
+                    kept.push((a, start - 1));
+                }
+                if b > end {
+                    kept.push((end + 1, b));
+                }
+                kept
+            })
+            .collect();
+    }
+
+    // Modificar um byte
+    fn edit_byte(&mut self, offset: usize, value: u8) {
+        if offset < self.data.len() && self.writable() && self.range_unlocked(offset, offset) {
+            // O estado salvo fica inalcançável se editarmos depois de desfazer além dele
+            if self.saved_depth.is_some_and(|depth| depth > self.undo_stack.len()) {
+                self.saved_depth = None;
+            }
+            self.undo_stack.push((offset, self.data[offset]));
+            self.redo_stack.clear();
+            self.data[offset] = value;
+            self.data_changed();
+            self.session_edits.insert(offset);
+            self.update_modified();
+        }
+    }
+
+    // Inverter um bit (0 = menos significativo) do byte, como uma edição comum
+    fn toggle_bit(&mut self, offset: usize, bit: u8) {
+        if offset < self.data.len() && bit < 8 {
+            self.pending_nibble = None;
+            self.edit_byte(offset, self.data[offset] ^ (1 << bit));
+        }
+    }
+
+    // Preencher um intervalo (inclusivo) com o mesmo byte; false se nada foi escrito (o motivo fica no rodapé)
+    fn fill_range(&mut self, start: usize, end: usize, value: u8) -> bool {
+        if self.data.is_empty() || !self.writable() {
+            return false;
+        }
+        let (start, end) = if start <= end { (start, end) } else { (end, start) };
+        let end = end.min(self.data.len() - 1);
+        if start > end || !self.range_unlocked(start, end) {
+            return false;
+        }
+        for offset in start..=end {
+            self.edit_byte(offset, value);
+        }
+        true
+    }
+
+    // Aplicar uma operação bit a bit com a máscara em cada byte do intervalo (inclusivo); false se nada foi escrito
+    fn apply_bitwise(&mut self, range: (usize, usize), op: BitOp, mask: u8) -> bool {
+        if self.data.is_empty() || !self.writable() {
+            return false;
+        }
+        let (start, end) = (range.0, range.1.min(self.data.len() - 1));
+        if start > end || !self.range_unlocked(start, end) {
+            return false;
+        }
+        for offset in start..=end {
+            let value = op.apply(self.data[offset], mask);
+            self.edit_byte(offset, value);
+        }
+        true
+    }
+
+    // Seleção atual ordenada e limitada ao tamanho dos dados
+    fn selection_range(&self) -> Option<(usize, usize)> {
+        let (a, b) = self.selection?;
+        let (start, end) = if a <= b { (a, b) } else { (b, a) };
+        if start >= self.data.len() {
+            return None;
+        }
+        Some((start, end.min(self.data.len() - 1)))
+    }
+
+    // Somar 1 ao byte, voltando a 0x00 depois de 0xFF
+    fn increment_byte(&mut self, offset: usize) {
+        if let Some(value) = self.data.get(offset) {
+            self.edit_byte(offset, value.wrapping_add(1));
+        }
+    }
+
+    // Subtrair 1 do byte, voltando a 0xFF depois de 0x00
+    fn decrement_byte(&mut self, offset: usize) {
+        if let Some(value) = self.data.get(offset) {
+            self.edit_byte(offset, value.wrapping_sub(1));
+        }
+    }
+
+    // Guardar uma cópia dos dados atuais com um nome, substituindo outra de mesmo nome
+    fn take_snapshot(&mut self, name: &str) {
+        self.snapshots.insert(name.to_string(), self.data.bytes().to_vec());
+    }
+
+    // Voltar os dados a uma cópia guardada; como o tamanho pode mudar, o histórico de desfazer é descartado
+    fn restore_snapshot(&mut self, name: &str) -> io::Result<()> {
+        if self.read_only {
+            return Err(read_only_error());
+        }
+        let snapshot = self
+            .snapshots
+            .get(name)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("instantâneo \"{}\" não existe", name)))?;
+        if !self.data.can_resize() {
+            return Err(fixed_size_error());
+        }
+        if self.replacement_touches_lock(snapshot) {
+            return Err(locked_error());
+        }
+        let snapshot = snapshot.clone();
+        *self.data_vec() = snapshot;
+        let len = self.data.len();
+        self.session_edits.retain(|&offset| offset < len);
+        if self.selection.is_some_and(|(start, end)| start.max(end) >= len) {
+            self.selection = None;
+        }
+        self.pending_nibble = None;
+        self.invalidate_history();
+        self.clamp_view();
+        self.clamp_cursor();
+        Ok(())
+    }
+
+    // Copiar os bytes selecionados para a área de transferência, retornando quantos foram copiados
+    fn copy(&mut self) -> usize {
+        match self.selection_range() {
+            Some((start, end)) => {
+                self.clipboard = self.data.slice(start..end + 1).into_owned();
+                self.clipboard.len()
+            }
+            None => 0,
+        }
+    }
+
+    // Colar sobrescrevendo a partir de um offset, sem crescer o arquivo; retorna quantos bytes foram escritos.
+    // Com 0 nada foi alterado e o motivo fica no rodapé.
+    fn paste(&mut self, at: usize) -> usize {
+        if self.clipboard.is_empty() {
+            self.set_status("Área de transferência vazia");
+            return 0;
+        }
+        if !self.writable() {
+            return 0;
+        }
+        if self.edit_mode == EditMode::Insert {
+            let clipboard = self.clipboard.clone();
+            return if self.insert_bytes(at, &clipboard) { clipboard.len() } else { 0 };
+        }
+        let count = self.clipboard.len().min(self.data.len().saturating_sub(at));
+        if count == 0 || !self.range_unlocked(at, at + count - 1) {
+            return 0;
+        }
+        for i in 0..count {
+            self.edit_byte(at + i, self.clipboard[i]);
+        }
+        count
+    }
+
+    // Gravar bytes de um arquivo a partir de `at`: no modo inserir eles entram no meio dos dados,
+    // no de sobrescrever só os que cabem até o fim do arquivo são gravados. Com 0 o motivo fica no rodapé.
+    fn import_over(&mut self, at: usize, bytes: &[u8]) -> usize {
+        if !self.writable() {
+            return 0;
+        }
+        if self.edit_mode == EditMode::Insert {
+            return if self.insert_bytes(at, bytes) { bytes.len() } else { 0 };
+        }
+        let count = bytes.len().min(self.data.len().saturating_sub(at));
+        if count == 0 || !self.range_unlocked(at, at + count - 1) {
+            return 0;
+        }
+        for (i, &byte) in bytes[..count].iter().enumerate() {
+            self.edit_byte(at + i, byte);
+        }
+        count
+    }
+
+    // Copiar a seleção para a área de transferência do sistema como hex separado por espaços
+    fn copy_to_system_clipboard(&self) -> io::Result<usize> {
+        let (start, end) = self
+            .selection_range()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "nenhuma seleção"))?;
+        let bytes = self.data.slice(start..end + 1);
+        let text: Vec<String> = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+        set_system_clipboard_text(text.join(" "))?;
+        Ok(bytes.len())
+    }
+
+    // Colar o hex da área de transferência do sistema como o Ctrl+V, sem trocar a área de transferência interna
+    fn paste_from_system_clipboard(&mut self, at: usize) -> io::Result<usize> {
+        let text = system_clipboard_text()?;
+        let bytes = parse_clipboard_hex(&text).ok_or_else(|| {
+            let preview: String = text.trim().chars().take(20).collect();
+            io::Error::new(io::ErrorKind::InvalidData, format!("o conteúdo não é hex: \"{}\"", preview))
+        })?;
+        let internal = mem::replace(&mut self.clipboard, bytes);
+        let count = self.paste(at);
+        self.clipboard = internal;
+        Ok(count)
+    }
+
+    // Console do arquivo aberto, pela extensão ou pelos bytes mágicos
+    fn console(&self) -> Option<Console> {
+        let extension = self
+            .rom_path
+            .as_ref()
+            .and_then(|path| path.extension())
+            .and_then(|ext| ext.to_str());
+        // Os bytes mágicos ficam no início; evita copiar o arquivo mapeado inteiro
+        let start = self.data.slice(0..self.data.len().min(0x200));
+        header::detect_console(&start, extension)
+    }
+
+    // Ler o cabeçalho do cartucho no layout do console detectado
+    fn parse_header(&self) -> Option<RomHeader> {
+        // O cabeçalho fica nos primeiros bytes; ler só eles evita copiar o arquivo inteiro a cada quadro
+        let start = self.data.slice(0..self.data.len().min(0x200));
+        header::parse(&start, self.data.len(), self.console()?)
+    }
+
+    // Jogo de Pokémon reconhecido pelo cabeçalho
+    fn detect_game(&self) -> Option<GameId> {
+        games::detect(&self.parse_header()?)
+    }
+
+    // Tamanho aceito pelos flashcarts: ROMs GBA precisam ter uma potência de 2; os outros arquivos não são conferidos
+    fn is_valid_rom_size(&self) -> bool {
+        self.console() != Some(Console::Gba) || self.data.len().is_power_of_two()
+    }
+
+    // Nome da espécie de número `index` na tabela do jogo detectado, decodificado pela tabela de caracteres
+    fn lookup_species(&self, index: u16) -> Option<String> {
+        self.lookup_name(&self.detect_game()?.species_names()?, index)
+    }
+
+    // Nome do golpe de número `index` na tabela do jogo detectado
+    fn lookup_move(&self, index: u16) -> Option<String> {
+        self.lookup_name(&self.detect_game()?.move_names()?, index)
+    }
+
+    fn lookup_name(&self, names: &games::NameTable, index: u16) -> Option<String> {
+        let position = index.checked_sub(names.first).filter(|&position| position < names.count)? as usize;
+        let start = match names.entry_len {
+            Some(len) => names.offset + position * len,
+            // Pular os nomes anteriores, um terminador por nome
+            None => {
+                let mut start = names.offset;
+                for _ in 0..position {
+                    start = self.find_byte(names.terminator, start)? + 1;
+                }
+                start
+            }
+        };
+        let end = match names.entry_len {
+            Some(len) => start.checked_add(len)?.min(self.data.len()),
+            None => self.find_byte(names.terminator, start)?,
+        };
+        let bytes = self.data.get_slice(start..end)?;
+        let len = bytes.iter().position(|&byte| byte == names.terminator).unwrap_or(bytes.len());
+        let empty = HashMap::new();
+        Some(table::decode(self.table.as_ref().unwrap_or(&empty), &self.text_codes, &bytes[..len]))
+    }
+
+    // Conferir o cabeçalho GBA (ponto de entrada, logotipo, valor fixo e checksum); vazio se estiver válido
+    fn check_gba_header(&self) -> Vec<&'static str> {
+        header::check_gba(&self.data.slice(0..self.data.len().min(0xC0)))
+    }
+
+    // ROMs de Game Boy / Game Boy Color
+    fn is_gb_rom(&self) -> bool {
+        self.console() == Some(Console::Gb)
+    }
+
+    // Checksum do cabeçalho GB (0x014D): x = x - byte - 1 sobre 0x0134..=0x014C
+    fn gb_header_checksum(&self) -> Option<u8> {
+        let header = self.data.get_slice(0x134..0x14D)?;
+        Some(header.iter().fold(0u8, |x, &byte| x.wrapping_sub(byte).wrapping_sub(1)))
+    }
+
+    // Checksum global GB (0x014E-0x014F): soma de todos os bytes exceto os próprios dois
+    fn gb_global_checksum(&self) -> Option<u16> {
+        if self.data.len() < 0x150 {
+            return None;
+        }
+        if let Some(sum) = self.gb_checksum_cache.get() {
+            return Some(sum);
+        }
+        // Somar em trechos, sem copiar o arquivo inteiro de uma vez; os próprios 2 bytes são descontados no fim
+        let mut sum = self.data.chunks(0..self.data.len()).fold(0u16, |sum, chunk| {
+            chunk.iter().fold(sum, |sum, &byte| sum.wrapping_add(byte as u16))
+        });
+        sum = sum.wrapping_sub(self.data[0x14E] as u16).wrapping_sub(self.data[0x14F] as u16);
+        self.gb_checksum_cache.set(Some(sum));
+        Some(sum)
+    }
+
+    // Gravar os checksums corrigidos; o global é calculado depois, pois inclui o byte 0x014D.
+    // Fora de uma ROM de Game Boy 0x014D-0x014F são código ou dados, então nada é escrito; o motivo fica no rodapé.
+    fn fix_gb_checksums(&mut self) -> bool {
+        if !self.is_gb_rom() {
+            self.set_status("Não é uma ROM de Game Boy: checksums não alterados");
+            return false;
+        }
+        let Some(header) = self.gb_header_checksum() else {
+            self.set_status("Arquivo pequeno demais para conter um cabeçalho Game Boy");
+            return false;
+        };
+        if !self.writable() || !self.range_unlocked(0x14D, 0x14F) {
+            return false;
+        }
+        self.edit_byte(0x14D, header);
+        let Some(global) = self.gb_global_checksum() else {
+            return false;
+        };
+        let [high, low] = global.to_be_bytes();
+        self.edit_byte(0x14E, high);
+        self.edit_byte(0x14F, low);
+        true
+    }
+
+    // Inserir um byte, deslocando os seguintes para frente; false se nada foi inserido (o motivo fica no rodapé)
+    fn insert_byte(&mut self, offset: usize, value: u8) -> bool {
+        self.insert_bytes(offset, &[value])
+    }
+
+    // Inserir uma sequência de bytes, deslocando os seguintes para frente; false se nada foi inserido
+    fn insert_bytes(&mut self, offset: usize, bytes: &[u8]) -> bool {
+        // Inserir no meio de um bloqueio mudaria os bytes protegidos; antes ou depois dele, ele só é deslocado
+        let inside_lock = offset > 0 && self.is_locked(offset - 1) && self.is_locked(offset);
+        if offset <= self.data.len()
+            && !bytes.is_empty()
+            && self.writable()
+            && self.resizable()
+            && (!inside_lock || self.range_unlocked(offset, offset))
+        {
+            self.data_vec().splice(offset..offset, bytes.iter().copied());
+            let count = bytes.len();
+            self.session_edits = self
+                .session_edits
+                .iter()
+                .map(|&edited| if edited >= offset { edited + count } else { edited })
+                .chain(offset..offset + count)
+                .collect();
+            for range in self.locked_ranges.iter_mut().filter(|(start, _)| *start >= offset) {
+                *range = (range.0 + count, range.1 + count);
+            }
+            self.invalidate_history();
+            return true;
+        }
+        false
+    }
+
+    // Remover um byte, deslocando os seguintes para trás; false se nada foi removido (o motivo fica no rodapé)
+    fn delete_byte(&mut self, offset: usize) -> bool {
+        if offset < self.data.len() && self.writable() && self.resizable() && self.range_unlocked(offset, offset) {
+            self.data_vec().remove(offset);
+            self.session_edits = self
+                .session_edits
+                .iter()
+                .filter(|&&edited| edited != offset)
+                .map(|&edited| if edited > offset { edited - 1 } else { edited })
+                .collect();
+            for range in self.locked_ranges.iter_mut().filter(|(start, _)| *start > offset) {
+                *range = (range.0 - 1, range.1 - 1);
+            }
+            self.invalidate_history();
+            self.clamp_view();
+            return true;
+        }
+        false
+    }
+
+    // Completar com o byte padrão até a próxima potência de 2, tamanho exigido por alguns emuladores; retorna quantos bytes foram adicionados
+    fn pad_to_power_of_two(&mut self) -> usize {
+        if self.data.is_empty() {
+            return 0;
+        }
+        let len = self.data.len();
+        self.pad_to(len.next_power_of_two(), self.default_fill);
+        self.data.len() - len
+    }
+
+    // Completar o arquivo com `fill` até `size` bytes; arquivos maiores ficam como estão
+    fn pad_to(&mut self, size: usize, fill: u8) {
+        let start = self.data.len();
+        if size > start && self.writable() && self.resizable() {
+            self.data_vec().resize(size, fill);
+            self.session_edits.extend(start..size);
+            self.invalidate_history();
+        }
+    }
+
+    // Remover os bytes `fill` do fim do arquivo, retornando quantos foram removidos
+    fn trim_trailing(&mut self, fill: u8) -> usize {
+        if !self.resizable() {
+            return 0;
+        }
+        let bytes = self.data.bytes();
+        let len = bytes.iter().rposition(|&byte| byte != fill).map_or(0, |last| last + 1);
+        let removed = bytes.len() - len;
+        drop(bytes);
+        // Cortar o fim não pode levar junto um trecho bloqueado
+        if removed > 0 && self.writable() && self.range_unlocked(len, len + removed - 1) {
+            self.data_vec().truncate(len);
+            self.session_edits.retain(|&edited| edited < len);
+            self.invalidate_history();
+            self.clamp_view();
+            self.clamp_cursor();
+            return removed;
+        }
+        0
+    }
+
+    // Cortar os bytes além do tamanho original; retorna quantos foram removidos
+    fn truncate_to_original(&mut self) -> usize {
+        let removed = self.data.len().saturating_sub(self.original_len);
+        if removed > 0 && self.writable() && self.resizable() && self.range_unlocked(self.original_len, self.data.len() - 1) {
+            let len = self.original_len;
+            self.data_vec().truncate(len);
+            self.session_edits.retain(|&edited| edited < len);
+            self.invalidate_history();
+            self.clamp_view();
+            self.clamp_cursor();
+            return removed;
+        }
+        0
+    }
+
+    // Descartar o que foi calculado a partir dos bytes (minimapa, contagem de diferenças, checksum GB)
+    fn data_changed(&self) {
+        self.minimap.take();
+        self.diff_cache.take();
+        self.gb_checksum_cache.take();
+    }
+
+    // Bytes diferentes dos da última gravação (ou da leitura), contando como diferentes os que sobram ou faltam no fim
+    fn diff_count(&self) -> usize {
+        if let Some(count) = self.diff_cache.get() {
+            return count;
+        }
+        let count = match (&self.original, self.data.changed_count()) {
+            (Some(original), _) => {
+                let data = self.data.bytes();
+                let common = original.len().min(data.len());
+                let changed = original[..common].iter().zip(&data[..common]).filter(|(a, b)| a != b).count();
+                changed + original.len().max(data.len()) - common
+            }
+            (None, count) => count.unwrap_or(0),
+        };
+        self.diff_cache.set(Some(count));
+        count
+    }
+
+    // Vetor dos dados, para operações que mudam o tamanho. Um arquivo mapeado é copiado para a memória,
+    // e os bytes do disco passam a ser o original de `diff_count`.
+    fn data_vec(&mut self) -> &mut Vec<u8> {
+        if self.original.is_none() {
+            self.original = self.data.disk_bytes();
+        }
+        self.data.vec_mut()
+    }
+
+    // Inserções e remoções deslocam os offsets registrados, então o histórico é descartado
+    fn invalidate_history(&mut self) {
+        self.data_changed();
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.saved_depth = None;
+        self.modified = true;
+    }
+
+    // Manter view_offset dentro dos dados caso o arquivo tenha diminuído
+    fn clamp_view(&mut self) {
+        if self.view_offset >= self.data.len() {
+            let last = self.data.len().saturating_sub(1);
+            self.view_offset = last - last % self.bytes_per_row;
+        }
+    }
+
+    // Levar o cursor ao último byte caso ele tenha ficado além do fim dos dados
+    fn clamp_cursor(&mut self) {
+        let (x, y) = self.cursor_pos;
+        let byte_idx = x.saturating_sub(10) / 3;
+        let offset = self.view_offset + y * self.bytes_per_row + byte_idx;
+        if offset >= self.data.len() {
+            self.goto_offset(self.data.len().saturating_sub(1));
+        }
+    }
+
+    // Desfazer a última alteração de byte, retornando o offset restaurado
+    fn undo(&mut self) -> Option<usize> {
+        if !self.writable() {
+            return None;
+        }
+        let (offset, previous) = self.undo_stack.pop()?;
+        self.redo_stack.push((offset, self.data[offset]));
+        self.data[offset] = previous;
+        self.data_changed();
+        self.update_modified();
+        Some(offset)
+    }
+
+    // Refazer a última alteração desfeita, retornando o offset reaplicado
+    fn redo(&mut self) -> Option<usize> {
+        if !self.writable() {
+            return None;
+        }
+        let (offset, value) = self.redo_stack.pop()?;
+        self.undo_stack.push((offset, self.data[offset]));
+        self.data[offset] = value;
+        self.data_changed();
+        self.update_modified();
+        Some(offset)
+    }
+
+    // O arquivo só está modificado se a pilha de desfazer divergir do último salvamento
+    fn update_modified(&mut self) {
+        self.modified = self.saved_depth != Some(self.undo_stack.len());
+    }
+
+    // Calcular o offset absoluto baseado na posição do cursor. A coluna é sempre a do lado
+    // hexadecimal; no painel ASCII o mesmo byte é destacado, então o offset vale para ambos.
+    fn get_cursor_offset(&self) -> Option<usize> {
+        let (x, y) = self.cursor_pos;
+        
+        // Verificar se o cursor está na área de bytes (não no endereço ou ASCII)
+        if (10..10 + self.bytes_per_row * 3).contains(&x) && x % 3 != 2 {
+            let byte_idx = (x - 10) / 3;
+            let offset = self.view_offset + y * self.bytes_per_row + byte_idx;
+            
+            if offset < self.data.len() {
+                return Some(offset);
+            }
+        }
+        
+        None
+    }
+
+    // Mover o cursor
+    fn move_cursor(&mut self, dx: isize, dy: isize) {
+        // x fica sempre alinhado ao início de um byte (10 + 3 * índice)
+        let last_x = 10 + (self.bytes_per_row - 1) * 3;
+        let new_x = (self.cursor_pos.0 as isize + dx).max(10).min(last_x as isize) as usize;
+        
+        // Passar da primeira ou da última linha visível rola a visualização junto
+        let rows = self.visible_rows() as isize;
+        let mut new_y = self.cursor_pos.1 as isize + dy;
+        let before = self.view_offset;
+        if new_y < 0 {
+            self.scroll(new_y);
+        } else if new_y >= rows {
+            self.scroll(new_y - rows + 1);
+        }
+        let scrolled = (self.view_offset as isize - before as isize) / self.bytes_per_row as isize;
+        new_y = (new_y - scrolled).clamp(0, rows - 1);
+
+        // Não descer além da última linha com dados
+        if !self.data.is_empty() {
+            let last_row = (self.data.len() - 1).saturating_sub(self.view_offset) / self.bytes_per_row;
+            new_y = new_y.min(last_row as isize);
+        }
+        
+        self.cursor_pos = (new_x, new_y as usize);
+        self.pending_nibble = None;
+    }
+
+    // Mover o cursor `dir` passos de `stride` bytes (ou do tamanho da seleção), atravessando linhas
+    fn move_cursor_by_stride(&mut self, dir: isize) {
+        let Some(offset) = self.get_cursor_offset() else {
+            return;
+        };
+        let stride = self.selection_range().map_or(self.stride, |(start, end)| end - start + 1);
+        let target = (offset as isize + dir * stride as isize).clamp(0, self.data.len() as isize - 1);
+        // move_cursor cuida da rolagem quando o destino sai da tela
+        let bytes_per_row = self.bytes_per_row as isize;
+        let (row, col) = (offset as isize / bytes_per_row, offset as isize % bytes_per_row);
+        let (target_row, target_col) = (target / bytes_per_row, target % bytes_per_row);
+        self.move_cursor((target_col - col) * 3, target_row - row);
+    }
+
+    // Início do registro que contém o offset e seu índice na tabela
+    fn record_at(&self, offset: usize) -> Option<(usize, usize)> {
+        let size = self.struct_size?;
+        let index = offset.checked_sub(self.struct_base)? / size;
+        Some((self.struct_base + index * size, index))
+    }
+
+    // Levar o cursor ao início do registro atual (dir = 0) ou de `dir` registros adiante/atrás
+    fn step_record(&mut self, dir: isize) -> Option<usize> {
+        let size = self.struct_size?;
+        let offset = self.get_cursor_offset()?;
+        let target = match self.record_at(offset) {
+            Some((_, index)) => (index as isize + dir).max(0) as usize,
+            // Antes da tabela, o primeiro passo para frente cai no registro 0
+            None if dir > 0 => 0,
+            None => return None,
+        };
+        // Não passar do último registro que começa dentro do arquivo
+        let last = (self.data.len() - 1).checked_sub(self.struct_base)? / size;
+        let target = target.min(last);
+        self.goto_offset(self.struct_base + target * size);
+        Some(target)
+    }
+
+    // Mover o cursor estendendo a seleção a partir da posição atual
+    fn extend_selection(&mut self, dx: isize, dy: isize) {
+        let anchor = self.selection.map(|(start, _)| start).or(self.get_cursor_offset());
+        self.move_cursor(dx, dy);
+        if let (Some(anchor), Some(offset)) = (anchor, self.get_cursor_offset()) {
+            self.selection = Some((anchor, offset));
+        }
+    }
+
+    // Avançar o cursor para o próximo byte, passando para a linha seguinte no fim da linha
+    fn advance_cursor(&mut self) {
+        if self.cursor_pos.0 + 3 < 10 + self.bytes_per_row * 3 {
+            self.move_cursor(3, 0);
+        } else {
+            self.cursor_pos.0 = 10;
+            self.move_cursor(0, 1);
+        }
+    }
+
+    // Alternar o cursor entre os painéis hexadecimal e ASCII
+    fn toggle_pane(&mut self) {
+        self.cursor_in_ascii = !self.cursor_in_ascii;
+        self.pending_nibble = None;
+    }
+
+    // Abrir ou fechar a visão dividida; o segundo painel começa na mesma posição do atual
+    fn toggle_split(&mut self) {
+        if self.split.take().is_some() {
+            return;
+        }
+        self.split = Some(SplitView {
+            view_offset: self.view_offset,
+            cursor: self.get_cursor_offset().unwrap_or(self.view_offset),
+            focus_right: false,
+        });
+        // A visão dividida mostra só os painéis hexadecimais
+        self.cursor_in_ascii = false;
+        self.pending_nibble = None;
+    }
+
+    // Passar o foco (cursor, rolagem e edição) para o outro painel da visão dividida
+    fn switch_split_focus(&mut self) {
+        let Some(split) = self.split else {
+            return;
+        };
+        let cursor = self.get_cursor_offset().unwrap_or(self.view_offset);
+        self.split = Some(SplitView {
+            view_offset: self.view_offset,
+            cursor,
+            focus_right: !split.focus_right,
+        });
+        // Os bytes por linha ou o tamanho podem ter mudado desde que o painel perdeu o foco
+        self.view_offset = split.view_offset - split.view_offset % self.bytes_per_row;
+        self.clamp_view();
+        self.goto_offset(split.cursor);
+    }
+
+    // Digitar um caractere no painel ASCII, gravando o byte e avançando o cursor
+    fn type_ascii(&mut self, byte: u8) {
+        if let Some(offset) = self.get_cursor_offset() {
+            match self.edit_mode {
+                EditMode::Overwrite => self.edit_byte(offset, byte),
+                EditMode::Insert => {
+                    self.insert_byte(offset, byte);
+                }
+            }
+            self.advance_cursor();
+        }
+    }
+
+    // Digitar um nibble hexadecimal sobre o byte do cursor
+    fn type_nibble(&mut self, nibble: u8) {
+        let Some(offset) = self.get_cursor_offset() else {
+            return;
+        };
+
+        match self.pending_nibble.take() {
+            None => self.pending_nibble = Some(nibble),
+            Some(high) => {
+                // No modo de inserção o byte só entra no arquivo quando os dois nibbles estão digitados
+                match self.edit_mode {
+                    EditMode::Overwrite => self.edit_byte(offset, (high << 4) | nibble),
+                    EditMode::Insert => {
+                        self.insert_byte(offset, (high << 4) | nibble);
+                    }
+                }
+                self.advance_cursor();
+            }
+        }
+    }
+
+    // Ir para um offset, rolando a visualização se necessário e posicionando o cursor no byte
+    fn goto_offset(&mut self, offset: usize) {
+        if self.data.is_empty() {
+            return;
+        }
+        let offset = offset.min(self.data.len() - 1);
+
+        let visible_end = self.view_offset + self.visible_rows() * self.bytes_per_row;
+        if offset < self.view_offset || offset >= visible_end {
+            self.view_offset = offset - offset % self.bytes_per_row;
+        }
+
+        let relative = offset - self.view_offset;
+        self.cursor_pos = ((relative % self.bytes_per_row) * 3 + 10, relative / self.bytes_per_row);
+        self.pending_nibble = None;
+    }
+
+    // Posicionar no offset inicial passado na linha de comando, avisando se for inválido
+    fn goto_start_offset(&mut self, input: &str) {
+        let Some(offset) = parse_hex(input) else {
+            self.set_status(format!("Aviso: offset inicial inválido: {}", input));
+            return;
+        };
+        if offset >= self.data.len() as u64 {
+            self.set_status(format!(
+                "Aviso: offset inicial 0x{:X} fora do arquivo ({} bytes); indo para o último byte",
+                offset,
+                self.data.len()
+            ));
+        }
+        self.goto_offset_top(offset.min(self.data.len() as u64) as usize);
+    }
+
+    // Ir para um offset deixando a linha dele no topo da visualização
+    fn goto_offset_top(&mut self, offset: usize) {
+        if self.data.is_empty() {
+            return;
+        }
+        let offset = offset.min(self.data.len() - 1);
+        self.view_offset = (offset - offset % self.bytes_per_row).min(self.max_view_offset());
+        self.goto_offset(offset);
+    }
+
+    // Ir para uma fração do arquivo (0-100%), com a linha no topo da visualização
+    fn goto_percent(&mut self, pct: f32) {
+        if self.data.is_empty() {
+            return;
+        }
+        let pct = if pct.is_nan() { 0.0 } else { pct.clamp(0.0, 100.0) };
+        let offset = ((self.data.len() - 1) as f64 * pct as f64 / 100.0) as usize;
+        self.goto_offset_top(offset - offset % self.bytes_per_row);
+    }
+
+    // Ir para o primeiro byte do arquivo
+    fn goto_start(&mut self) {
+        self.view_offset = 0;
+        self.cursor_pos = (10, 0);
+        self.pending_nibble = None;
+    }
+
+    // Ir para o último byte, com a visualização encostada no fim para a última linha ficar inteira na tela
+    fn goto_end(&mut self) {
+        if self.data.is_empty() {
+            return self.goto_start();
+        }
+        self.view_offset = self.max_view_offset();
+        self.goto_offset(self.data.len() - 1);
+    }
+
+    // Buscar a próxima ocorrência de uma sequência de bytes a partir de um offset
+    fn search(&self, pattern: &[u8], start: usize) -> Option<usize> {
+        if pattern.is_empty() || start >= self.data.len() {
+            return None;
+        }
+        self.data
+            .slice(start..self.data.len())
+            .windows(pattern.len())
+            .position(|window| window == pattern)
+            .map(|pos| start + pos)
+    }
+
+    // Como `search`, mas as posições None do padrão aceitam qualquer byte
+    fn search_pattern(&self, pattern: &[Option<u8>], start: usize) -> Option<usize> {
+        if pattern.is_empty() || start >= self.data.len() {
+            return None;
+        }
+        self.data
+            .slice(start..self.data.len())
+            .windows(pattern.len())
+            .position(|window| {
+                window
+                    .iter()
+                    .zip(pattern)
+                    .all(|(byte, expected)| expected.is_none_or(|value| value == *byte))
+            })
+            .map(|pos| start + pos)
+    }
+
+    // Buscar a próxima ocorrência de um texto ASCII a partir de um offset
+    fn search_ascii(&self, needle: &str, start: usize) -> Option<usize> {
+        let needle = needle.as_bytes();
+        if needle.is_empty() || start >= self.data.len() {
+            return None;
+        }
+        self.data
+            .slice(start..self.data.len())
+            .windows(needle.len())
+            .position(|window| {
+                if self.ignore_case {
+                    window.eq_ignore_ascii_case(needle)
+                } else {
+                    window == needle
+                }
+            })
+            .map(|pos| start + pos)
+    }
+
+    // Buscar o último padrão a partir de um offset, recomeçando do início do arquivo se preciso
+    fn find_from(&mut self, start: usize) -> Option<usize> {
+        let found = match self.last_search.as_ref()? {
+            SearchPattern::Bytes(pattern) => {
+                self.search_pattern(pattern, start).or_else(|| self.search_pattern(pattern, 0))?
+            }
+            SearchPattern::Text(needle) => {
+                self.search_ascii(needle, start).or_else(|| self.search_ascii(needle, 0))?
+            }
+        };
+        self.push_nav();
+        self.goto_offset(found);
+        Some(found)
+    }
+
+    // Buscar o último padrão depois do cursor
+    fn find_next(&mut self) -> Option<usize> {
+        let start = self.get_cursor_offset().map_or(self.view_offset, |offset| offset + 1);
+        self.find_from(start)
+    }
+
+    // Offsets de todas as ocorrências de uma sequência de bytes, sem sobreposição
+    fn find_all(&self, pattern: &[u8]) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut start = 0;
+        while let Some(offset) = self.search(pattern, start) {
+            found.push(offset);
+            start = offset + pattern.len();
+        }
+        found
+    }
+
+    // Quantas vezes um byte aparece no arquivo inteiro
+    fn count_occurrences(&self, value: u8) -> usize {
+        self.data.chunks(0..self.data.len()).map(|piece| piece.iter().filter(|&&byte| byte == value).count()).sum()
+    }
+
+    // Substituir todas as ocorrências por um padrão do mesmo tamanho, retornando quantas foram trocadas.
+    // As ocorrências que tocam um bloqueio ficam como estão.
+    fn replace_all(&mut self, find: &[u8], replace: &[u8]) -> usize {
+        if find.is_empty() || find.len() != replace.len() || !self.writable() {
+            return 0;
+        }
+        let found = self.find_all(find);
+        let mut replaced = 0;
+        for &offset in &found {
+            if self.overlaps_lock(offset, offset + find.len() - 1) {
+                continue;
+            }
+            for (i, &value) in replace.iter().enumerate() {
+                if self.data[offset + i] != value {
+                    self.edit_byte(offset + i, value);
+                }
+            }
+            replaced += 1;
+        }
+        replaced
+    }
+
+    // Posicionar o cursor a partir de uma célula de caractere clicada (coluna, linha)
+    fn click_to_cursor(&mut self, col: usize, row: usize) -> bool {
+        // As colunas abaixo são as da visão normal
+        if self.split.is_some() {
+            return false;
+        }
+        let grid_top = self.grid_top();
+        if row < grid_top || row - grid_top >= self.visible_rows() {
+            return false;
+        }
+
+        let hex_end = 10 + self.bytes_per_row * 3;
+        let ascii_start = hex_end + 3; // Separador " | " antes do painel ASCII
+        let (byte_idx, in_ascii) = if (10..hex_end).contains(&col) {
+            ((col - 10) / 3, false)
+        } else if (ascii_start..ascii_start + self.bytes_per_row * self.ascii_width()).contains(&col) {
+            ((col - ascii_start) / self.ascii_width(), true)
+        } else {
+            return false;
+        };
+
+        let y = row - grid_top;
+        if self.view_offset + y * self.bytes_per_row + byte_idx >= self.data.len() {
+            return false;
+        }
+
+        self.cursor_pos = (byte_idx * 3 + 10, y);
+        self.cursor_in_ascii = in_ascii;
+        self.pending_nibble = None;
+        true
+    }
+
+    // Alterar a quantidade de bytes por linha (4 a 32), mantendo o cursor no mesmo byte
+    fn set_bytes_per_row(&mut self, bytes_per_row: usize) {
+        let offset = self.get_cursor_offset();
+        self.bytes_per_row = bytes_per_row.clamp(4, 32);
+        self.view_offset -= self.view_offset % self.bytes_per_row;
+        self.cursor_pos = (10, 0);
+        if let Some(offset) = offset {
+            self.goto_offset(offset);
+        }
+    }
+
+    // CRC32 e MD5 do intervalo (inclusivo) ou, com None, do arquivo inteiro
+    fn checksum(&self, range: Option<(usize, usize)>) -> Checksums {
+        let range = match range {
+            Some((start, end)) => start..end.min(self.data.len().saturating_sub(1)) + 1,
+            None => 0..self.data.len(),
+        };
+        let mut hasher = checksum::Hasher::default();
+        for piece in self.data.chunks(range) {
+            hasher.update(&piece);
+        }
+        hasher.finish()
+    }
+
+    // Interpretar os bytes a partir de um offset como diferentes tipos numéricos
+    fn inspect(&self, offset: usize) -> InspectResult {
+        let end = offset.saturating_add(4).min(self.data.len());
+        inspect::inspect(&self.data.slice(offset.min(end)..end), offset, self.endianness)
+    }
+
+    // Resolver o ponteiro GBA (32 bits little-endian, base 0x08000000) em um offset do arquivo
+    fn follow_pointer(&self, offset: usize) -> Option<usize> {
+        let bytes = self.data.get_slice(offset..offset.checked_add(4)?)?;
+        let pointer = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        // A ROM ocupa 0x08000000-0x09FFFFFF (32 MB)
+        if !(GBA_ROM_BASE..GBA_ROM_BASE + 0x0200_0000).contains(&pointer) {
+            return None;
+        }
+        let target = pointer - GBA_ROM_BASE;
+        (target < self.data.len()).then_some(target)
+    }
+
+    // Seguir o ponteiro sob o cursor, guardando a origem para poder voltar
+    fn jump_to_pointer(&mut self) -> Option<usize> {
+        let offset = self.get_cursor_offset()?;
+        let target = self.follow_pointer(offset)?;
+        self.jump_stack.push(offset);
+        self.push_nav();
+        self.goto_offset(target);
+        Some(target)
+    }
+
+    // Voltar para a origem do último ponteiro seguido
+    fn jump_back(&mut self) -> Option<usize> {
+        let offset = self.jump_stack.pop()?;
+        self.goto_offset(offset);
+        Some(offset)
+    }
+
+    // Posição atual (view_offset, offset do cursor) para o histórico de navegação
+    fn nav_position(&self) -> (usize, usize) {
+        (self.view_offset, self.get_cursor_offset().unwrap_or(self.view_offset))
+    }
+
+    // Guardar a posição atual antes de um salto (ir para, busca, ponteiro...); um salto novo descarta o avanço
+    fn push_nav(&mut self) {
+        let position = self.nav_position();
+        if self.nav_back.last() != Some(&position) {
+            self.nav_back.push(position);
+            if self.nav_back.len() > NAV_HISTORY_LEN {
+                self.nav_back.remove(0);
+            }
+        }
+        self.nav_forward.clear();
+    }
+
+    // Voltar à posição de antes do último salto, retornando o offset do cursor restaurado
+    fn nav_back(&mut self) -> Option<usize> {
+        let position = self.nav_back.pop()?;
+        self.nav_forward.push(self.nav_position());
+        Some(self.restore_nav(position))
+    }
+
+    // Refazer o salto desfeito por nav_back
+    fn nav_forward(&mut self) -> Option<usize> {
+        let position = self.nav_forward.pop()?;
+        self.nav_back.push(self.nav_position());
+        Some(self.restore_nav(position))
+    }
+
+    fn restore_nav(&mut self, (view_offset, offset): (usize, usize)) -> usize {
+        self.view_offset = view_offset.min(self.max_view_offset());
+        self.goto_offset(offset);
+        self.get_cursor_offset().unwrap_or(offset)
+    }
+
+    // Escrever em `at` o ponteiro GBA (little-endian, base 0x08000000) para o offset `target`
+    fn write_pointer(&mut self, at: usize, target: usize) -> io::Result<()> {
+        if target >= self.data.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "destino fora do arquivo"));
+        }
+        if self.data.len().saturating_sub(at) < 4 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "não há 4 bytes no cursor"));
+        }
+        if !self.writable() {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "somente leitura"));
+        }
+        if self.overlaps_lock(at, at + 3) {
+            return Err(locked_error());
+        }
+        let pointer = (GBA_ROM_BASE + target) as u32;
+        for (i, byte) in pointer.to_le_bytes().into_iter().enumerate() {
+            self.edit_byte(at + i, byte);
+        }
+        Ok(())
+    }
+
+    // Campos do modelo lidos a partir de `offset`, com o valor de cada um já decodificado
+    fn apply_template(&self, offset: usize, template: &template::Template) -> Vec<template::FieldView> {
+        template
+            .fields
+            .iter()
+            .map(|field| {
+                let at = offset.saturating_add(field.offset);
+                let value = at
+                    .checked_add(field.kind.size())
+                    .and_then(|end| self.data.get_slice(at..end))
+                    .map(|bytes| field.kind.decode(&bytes, self.endianness));
+                template::FieldView { name: field.name.clone(), kind: field.kind, offset: at, value }
+            })
+            .collect()
+    }
+
+    // Campo do modelo aplicado que contém o offset
+    fn template_field_at(&self, offset: usize) -> Option<template::FieldView> {
+        let (template, base) = self.template.as_ref()?;
+        self.apply_template(*base, template)
+            .into_iter()
+            .find(|field| field.contains(offset))
+    }
+
+    // Escrever um campo a partir do texto digitado, no formato em que ele é exibido
+    fn write_field(&mut self, field: &template::FieldView, input: &str) -> io::Result<()> {
+        let bytes = field.kind.encode(input, self.endianness).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("valor inválido para {}: {}", field.kind.name(), input.trim()))
+        })?;
+        if field.value.is_none() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "o campo passa do fim do arquivo"));
+        }
+        if !self.writable() {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "somente leitura"));
+        }
+        if !bytes.is_empty() && self.overlaps_lock(field.offset, field.offset + bytes.len() - 1) {
+            return Err(locked_error());
+        }
+        for (i, byte) in bytes.into_iter().enumerate() {
+            self.edit_byte(field.offset + i, byte);
+        }
+        Ok(())
+    }
+
+    // Linhas do painel do modelo: o título e os campos, quatro por linha, com o campo do cursor destacado
+    fn template_lines(&self) -> Vec<String> {
+        let Some((template, base)) = &self.template else {
+            return Vec::new();
+        };
+        let cursor = self.get_cursor_offset();
+        let fields: Vec<String> = self
+            .apply_template(*base, template)
+            .iter()
+            .map(|field| {
+                let text = format!("{}={}", field.name, field.value.as_deref().unwrap_or("-"));
+                let under_cursor = cursor.is_some_and(|offset| field.contains(offset));
+                if under_cursor {
+                    Theme::paint(&self.theme.cursor, &text)
+                } else {
+                    text
+                }
+            })
+            .collect();
+        let mut lines = vec![format!("Modelo {} em 0x{:08X} ({} bytes):", template.name, base, template.size())];
+        lines.extend(fields.chunks(TEMPLATE_FIELDS_PER_LINE).map(|chunk| format!("  {}", chunk.join("  "))));
+        lines
+    }
+
+    // Aplicar um roteiro de edição (ver script.rs), retornando quantos comandos foram aplicados.
+    // Todas as linhas são conferidas antes: com alguma inválida, fora do arquivo ou num intervalo bloqueado, nada é alterado.
+    fn run_script(&mut self, text: &str) -> io::Result<usize> {
+        if self.read_only {
+            return Err(read_only_error());
+        }
+        let script::Script { commands, mut errors } = script::parse(text);
+        let len = self.data.len();
+        for &(line, ref command) in &commands {
+            // O parse já recusa os comandos cujo intervalo não cabe em um usize
+            let Some((start, end)) = command.span() else {
+                continue;
+            };
+            if end >= len {
+                errors.push((line, format!("0x{:X} fora do arquivo", end)));
+            } else if self.overlaps_lock(start, end) {
+                errors.push((line, format!("0x{:X}-0x{:X} está bloqueado", start, end)));
+            } else if let script::Command::Pointer { target, .. } = *command {
+                if target >= len {
+                    errors.push((line, format!("destino 0x{:X} fora do arquivo", target)));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            errors.sort_by_key(|&(line, _)| line);
+            let errors: Vec<String> = errors.iter().map(|(line, error)| format!("linha {}: {}", line, error)).collect();
+            return Err(io::Error::new(io::ErrorKind::InvalidData, errors.join("; ")));
+        }
+
+        for (_, command) in &commands {
+            match *command {
+                script::Command::Set { offset, ref bytes } => {
+                    for (i, &byte) in bytes.iter().enumerate() {
+                        self.edit_byte(offset + i, byte);
+                    }
+                }
+                script::Command::Fill { start, end, value } => {
+                    self.fill_range(start, end, value);
+                }
+                script::Command::Pointer { at, target } => self.write_pointer(at, target)?,
+            }
+        }
+        Ok(commands.len())
+    }
+
+    // Inteiro sem sinal de `width` bytes (até 8) a partir de `at`
+    fn read_int(&self, at: usize, width: usize, endian: Endian) -> Option<u64> {
+        let mut bytes = self.data.get_slice(at..at.checked_add(width)?)?.to_vec();
+        if endian == Endian::Little {
+            bytes.reverse();
+        }
+        Some(bytes.iter().fold(0u64, |value, &byte| (value << 8) | byte as u64))
+    }
+
+    // Escrever `value` em `width` bytes (1, 2 ou 4) a partir de `at`, cada byte com a própria entrada de desfazer
+    fn write_int(&mut self, at: usize, width: usize, value: u64, endian: Endian) -> io::Result<()> {
+        if ![1, 2, 4].contains(&width) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "a largura deve ser 1, 2 ou 4 bytes"));
+        }
+        if value >> (width * 8) != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("o valor não cabe em {} bytes", width)));
+        }
+        if self.data.len().saturating_sub(at) < width {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("não há {} bytes no cursor", width)));
+        }
+        if !self.writable() {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "somente leitura"));
+        }
+        if self.overlaps_lock(at, at + width - 1) {
+            return Err(locked_error());
+        }
+        let mut bytes = value.to_le_bytes()[..width].to_vec();
+        if endian == Endian::Big {
+            bytes.reverse();
+        }
+        for (i, byte) in bytes.into_iter().enumerate() {
+            self.edit_byte(at + i, byte);
+        }
+        Ok(())
+    }
+
+    // Primeira ocorrência do byte a partir de `start`
+    fn find_byte(&self, value: u8, start: usize) -> Option<usize> {
+        let bytes = self.data.slice(start.min(self.data.len())..self.data.len());
+        bytes.iter().position(|&byte| byte == value).map(|i| start + i)
+    }
+
+    // Última ocorrência do byte antes de `end`
+    fn rfind_byte(&self, value: u8, end: usize) -> Option<usize> {
+        let bytes = self.data.slice(0..end.min(self.data.len()));
+        bytes.iter().rposition(|&byte| byte == value)
+    }
+
+    // Próxima sequência de pelo menos `min_len` bytes de preenchimento a partir de `start`: (offset, tamanho)
+    fn find_free_space(&self, min_len: usize, start: usize) -> Option<(usize, usize)> {
+        if min_len == 0 || start >= self.data.len() {
+            return None;
+        }
+        let bytes = self.data.slice(start..self.data.len());
+        let mut run_start = None;
+        for (i, &byte) in bytes.iter().chain([!self.free_byte].iter()).enumerate() {
+            match (byte == self.free_byte, run_start) {
+                (true, None) => run_start = Some(i),
+                // O byte extra no fim encerra uma sequência que vai até o fim do arquivo
+                (false, Some(run)) if i - run >= min_len => return Some((start + run, i - run)),
+                (false, Some(_)) => run_start = None,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // Gerar um dump hexadecimal em texto no mesmo layout de render(); range é inclusivo e None exporta o arquivo todo
+    fn export_hexdump(&self, range: Option<(usize, usize)>) -> String {
+        let path = self.rom_path.as_ref().map_or("(sem arquivo)".to_string(), |p| p.display().to_string());
+        let mut dump = format!("Arquivo: {} ({} bytes)\n", path, self.data.len());
+        if self.data.is_empty() {
+            return dump;
+        }
+
+        let (start, end) = range.unwrap_or((0, self.data.len() - 1));
+        let end = end.min(self.data.len() - 1);
+        if start > end {
+            return dump;
+        }
+        dump += &format!("Intervalo: 0x{:08X}-0x{:08X} ({} bytes)\n", start, end, end - start + 1);
+
+        let mut row_offset = start;
+        while row_offset <= end {
+            let row_end = (row_offset + self.bytes_per_row).min(end + 1);
+            let row = self.data.slice(row_offset..row_end);
+
+            dump += &format!("0x{:08X} |", row_offset);
+            for byte in row.iter() {
+                dump += &format!(" {:02X}", byte);
+            }
+            dump += &"   ".repeat(self.bytes_per_row - row.len());
+            dump += " | ";
+            for &byte in row.iter() {
+                dump.push(if (32..=126).contains(&byte) { byte as char } else { '.' });
+            }
+            dump.push('\n');
+
+            row_offset = row_end;
+        }
+        dump
+    }
+
+    // Gravar os bytes do intervalo (inclusivo) como estão em um arquivo .bin separado
+    fn export_range(&self, start: usize, end: usize, path: &str) -> io::Result<usize> {
+        let bytes = self
+            .data
+            .get_slice(start..end.min(self.data.len().saturating_sub(1)) + 1)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "intervalo fora do arquivo"))?;
+        fs::write(path, &bytes)?;
+        Ok(bytes.len())
+    }
+
+    // Gerar um array C com os bytes do intervalo (inclusivo), 16 por linha, e sua constante de tamanho
+    fn to_c_array(&self, start: usize, end: usize, name: &str) -> String {
+        let bytes = self
+            .data
+            .get_slice(start..end.min(self.data.len().saturating_sub(1)) + 1)
+            .unwrap_or_default();
+
+        let mut out = format!("// {} bytes de 0x{:08X}\n", bytes.len(), start);
+        out += "#include <stdint.h>\n\n";
+        out += &format!("const uint8_t {}[] = {{\n", name);
+        for line in bytes.chunks(16) {
+            let values: Vec<String> = line.iter().map(|byte| format!("0x{:02X}", byte)).collect();
+            out += &format!("    {},\n", values.join(", "));
+        }
+        out += "};\n";
+        out += &format!("const unsigned int {}_len = {};\n", name, bytes.len());
+        out
+    }
+
+    // Intervalo (inclusivo) atualmente visível na tela
+    fn visible_range(&self) -> Option<(usize, usize)> {
+        if self.view_offset >= self.data.len() {
+            return None;
+        }
+        let end = (self.view_offset + self.visible_rows() * self.bytes_per_row).min(self.data.len());
+        Some((self.view_offset, end - 1))
+    }
+
+    // Rolar a visualização `delta` linhas (negativo sobe, positivo desce)
+    fn scroll(&mut self, delta: isize) {
+        let bytes = delta.unsigned_abs().saturating_mul(self.bytes_per_row);
+        if delta < 0 {
+            self.view_offset = self.view_offset.saturating_sub(bytes);
+        } else if delta > 0 {
+            self.view_offset = self.view_offset.saturating_add(bytes).min(self.max_view_offset());
+        }
+    }
+
+    // Colunas por byte no painel de texto; a tabela de caracteres usa sempre uma
+    fn ascii_width(&self) -> usize {
+        if self.table.is_some() {
+            1
+        } else {
+            self.ascii_mode.cell_width()
+        }
+    }
+
+    // Até `count` cores BGR555 (2 bytes cada) a partir de `offset`, em 0xRRGGBB
+    fn parse_palette(&self, offset: usize, count: usize) -> Vec<u32> {
+        let end = offset.saturating_add(count * 2).min(self.data.len());
+        let bytes = self.data.slice(offset.min(end)..end);
+        bytes
+            .chunks_exact(2)
+            .map(|pair| tiles::bgr555_to_rgb(u16::from_le_bytes([pair[0], pair[1]])))
+            .collect()
+    }
+
+    // Fase do cursor piscando: muda a cada meio período e é sempre 0 sem o pisca
+    fn blink_phase(&self) -> u128 {
+        self.cursor_blink
+            .map_or(0, |rate| self.started.elapsed().as_millis() / rate.as_millis().max(1))
+    }
+
+    // O contorno do cursor aparece nas fases pares
+    fn cursor_shown(&self) -> bool {
+        self.blink_phase().is_multiple_of(2)
+    }
+
+    // Quadro da janela com os gráficos a partir do cursor e o minimapa, ou None se nenhum dos dois estiver ligado
+    fn graphics_frame(&self, width: usize, height: usize) -> Option<Vec<u32>> {
+        if width == 0 || height == 0 || (self.graphics_view.is_none() && !self.show_minimap) {
+            return None;
+        }
+        let start = self.get_cursor_offset().unwrap_or(self.view_offset).min(self.data.len());
+        let mut frame = match self.graphics_view {
+            Some(GraphicsView::Palette) => {
+                let rows = height / (width / PALETTE_COLUMNS).max(2) + 1;
+                let colors = self.parse_palette(start, rows * PALETTE_COLUMNS);
+                let mut frame = tiles::render_swatches(&colors, PALETTE_COLUMNS, width, height);
+                // O quadro começa no cursor, então ele é sempre a primeira amostra ou o primeiro tile
+                if self.cursor_shown() {
+                    tiles::outline(&mut frame, width, height, (0, 0), (width / PALETTE_COLUMNS).max(2), tiles::CURSOR);
+                }
+                frame
+            }
+            Some(GraphicsView::Tiles) => {
+                let tile_size = 8 * tiles::scale(self.tiles_wide, width);
+                let tile_rows = height / tile_size + 1;
+                let end = (start + tile_rows * self.tiles_wide * tiles::TILE_BYTES).min(self.data.len());
+                let palette = self.tile_palette.map_or(tiles::GRAYSCALE, |(_, colors)| colors);
+                let mut frame = tiles::render_4bpp(&self.data.slice(start..end), self.tiles_wide, &palette, width, height);
+                if self.cursor_shown() {
+                    tiles::outline(&mut frame, width, height, (0, 0), tile_size, tiles::CURSOR);
+                }
+                frame
+            }
+            None => vec![0; width * height],
+        };
+        if self.show_minimap {
+            self.draw_minimap(&mut frame, width, height);
+        }
+        Some(frame)
+    }
+
+    // Pintar o minimapa na coluna da direita, com as bordas brancas nas linhas do trecho visível.
+    // As cores só são recalculadas depois de uma edição ou quando a altura da janela muda.
+    fn draw_minimap(&self, frame: &mut [u32], width: usize, height: usize) {
+        let mut cache = self.minimap.borrow_mut();
+        if cache.as_ref().is_none_or(|(rows, _)| *rows != height) {
+            *cache = Some((height, minimap::colors(self.data.len(), height, |range| self.data.chunks(range))));
+        }
+        let Some((_, colors)) = cache.as_ref() else {
+            return;
+        };
+        let chunk = minimap::chunk_len(self.data.len(), height);
+        let visible_end = self.view_offset + self.visible_rows() * self.bytes_per_row;
+        let (first, last) = (self.view_offset / chunk, visible_end.saturating_sub(1) / chunk);
+        let left = width.saturating_sub(MINIMAP_WIDTH);
+        // A linha do cursor fica inteira na cor do cursor enquanto ele estiver aceso
+        let cursor = self.get_cursor_offset().filter(|_| self.cursor_shown()).map(|offset| offset / chunk);
+        for (y, &color) in colors.iter().enumerate() {
+            let row = &mut frame[y * width + left..(y + 1) * width];
+            if cursor == Some(y) {
+                row.fill(tiles::CURSOR);
+                continue;
+            }
+            row.fill(color);
+            if (first..=last).contains(&y) {
+                row[0] = 0xFFFFFF;
+                row[row.len() - 1] = 0xFFFFFF;
+            }
+        }
+    }
+
+    // Offset do trecho sob uma linha de pixels do minimapa, ou None fora da coluna
+    fn minimap_offset(&self, x: usize, y: usize, width: usize, height: usize) -> Option<usize> {
+        if !self.show_minimap || x < width.saturating_sub(MINIMAP_WIDTH) || self.data.is_empty() {
+            return None;
+        }
+        let offset = y * minimap::chunk_len(self.data.len(), height);
+        (offset < self.data.len()).then_some(offset)
+    }
+
+    // Offset da cor sob um ponto da janela na visão de paleta
+    fn swatch_at(&self, x: usize, y: usize, width: usize) -> Option<usize> {
+        let index = tiles::swatch_at(x, y, PALETTE_COLUMNS, width)?;
+        let offset = self.get_cursor_offset()? + index * 2;
+        (offset + 1 < self.data.len()).then_some(offset)
+    }
+
+    // Offset do tile sob um ponto da janela na visão de gráficos
+    fn tile_at(&self, x: usize, y: usize, width: usize) -> Option<usize> {
+        let tile_size = 8 * tiles::scale(self.tiles_wide, width);
+        let column = x / tile_size;
+        if column >= self.tiles_wide {
+            return None;
+        }
+        let start = self.get_cursor_offset()?;
+        let offset = start + ((y / tile_size) * self.tiles_wide + column) * tiles::TILE_BYTES;
+        (offset < self.data.len()).then_some(offset)
+    }
+
+    // Quantidade de linhas de dados desenhadas por render()
+    fn visible_rows(&self) -> usize {
+        // Ajustar para as linhas de cabeçalho e para o painel do modelo, se houver
+        let template = self.template.as_ref().map_or(0, |(template, _)| 1 + template.fields.len().div_ceil(TEMPLATE_FIELDS_PER_LINE));
+        self.screen_rows.saturating_sub(10 + template).max(1)
+    }
+
+    // Ajustar a grade ao novo tamanho da janela, mantendo o byte do cursor na tela
+    fn resize(&mut self, rows: usize) {
+        if rows == self.screen_rows {
+            return;
+        }
+        let offset = self.get_cursor_offset();
+        let pending = self.pending_nibble;
+        self.screen_rows = rows;
+        if let Some(offset) = offset {
+            self.goto_offset(offset);
+            self.pending_nibble = pending;
+        }
+        self.dirty = true;
+    }
+
+    // Maior view_offset (alinhado à linha) que ainda mantém a última linha na tela
+    fn max_view_offset(&self) -> usize {
+        let last = self.data.len().saturating_sub(1);
+        let last_row = last - last % self.bytes_per_row;
+        last_row.saturating_sub((self.visible_rows() - 1) * self.bytes_per_row)
+    }
+
+    // Endereço da coluna de offsets: absoluto ou, com uma base definida, "+0x..."/"-0x..." relativo a ela
+    fn format_address(&self, offset: usize) -> String {
+        match self.base_offset {
+            // Um dígito a menos para manter a largura da coluna
+            Some(base) if offset >= base => format!("+{}", self.address_base.format(offset - base, 7)),
+            Some(base) => format!("-{}", self.address_base.format(base - offset, 7)),
+            None => self.address_base.format(offset, 8),
+        }
+    }
+
+    // Offset digitado no "ir para": com uma base definida, "+X" e "-X" são relativos a ela
+    fn resolve_offset(&self, input: &str) -> Option<usize> {
+        if let Some(base) = self.base_offset {
+            if let Some(delta) = input.strip_prefix('+') {
+                return base.checked_add(self.address_base.parse(delta)?);
+            }
+            if let Some(delta) = input.strip_prefix('-') {
+                return base.checked_sub(self.address_base.parse(delta)?);
+            }
+        }
+        self.address_base.parse(input)
+    }
+
+    // Posição do cursor (ou do topo da visualização) em relação ao tamanho do arquivo
+    fn position_status(&self) -> Option<String> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let offset = self.get_cursor_offset().unwrap_or(self.view_offset);
+        let percent = (offset + 1) as f64 * 100.0 / self.data.len() as f64;
+        let mut line = format!(
+            "Posição: {} / {} ({:.1}%)",
+            self.address_base.format(offset, 8),
+            self.address_base.format(self.data.len() - 1, 8),
+            percent
+        );
+        if let Some(base) = self.base_offset {
+            line += &format!(" | Base: {} ({})", self.address_base.format(base, 8), self.format_address(offset));
+        }
+        if let (Some((start, index)), Some(size)) = (self.record_at(offset), self.struct_size) {
+            line += &format!(" | Registro {} +{} de {} bytes", index, offset - start, size);
+        }
+        Some(line)
+    }
+
+    // Linhas da grade ocupadas pelo indicador da barra de rolagem (início, fim exclusivo)
+    fn scrollbar_thumb(&self) -> (usize, usize) {
+        let rows = self.visible_rows();
+        let total_rows = self.data.len().div_ceil(self.bytes_per_row).max(1);
+        if total_rows <= rows {
+            return (0, rows);
+        }
+        let first_row = self.view_offset / self.bytes_per_row;
+        let len = (rows * rows / total_rows).max(1);
+        let start = (first_row * rows / total_rows).min(rows - len);
+        (start, start + len)
+    }
+
+    // Linhas do cabeçalho exibidas acima da grade de dados
+    fn header_lines(&self) -> Vec<String> {
+        let mut lines = vec!["=== Editor Hexadecimal para ROMs de Pokémon ===".to_string()];
+        if self.read_only {
+            lines[0] += " [SOMENTE LEITURA]";
+        }
+        if self.tabs.len() > 1 {
+            lines.push(self.tab_bar());
+        }
+        if let Some(ref path) = self.rom_path {
+            let name = if self.is_stdin() { "(entrada padrão)".to_string() } else { path.display().to_string() };
+            let diffs = self.diff_count();
+            let mut line = format!("Arquivo: {} ({}{}{})", 
+                    name, 
+                    self.data.len(), 
+                    if self.modified { ", modificado" } else { "" },
+                    if diffs > 0 { format!(", {} bytes diferem do arquivo salvo", diffs) } else { String::new() });
+            if let Some(game) = self.detect_game() {
+                line += &format!(" | Jogo: {}", game.name());
+            }
+            line += &format!(" | Modo: {}", self.edit_mode.name());
+            if let Some((start, end)) = self.selection_range() {
+                line += &format!(
+                    " | Seleção: {}-{} ({} bytes)",
+                    self.address_base.format(start, 8),
+                    self.address_base.format(end, 8),
+                    end - start + 1
+                );
+            }
+            lines.push(line);
+            if self.data.len() != self.original_len {
+                lines.push(format!(
+                    "Atenção: tamanho alterado de {} para {} bytes{} | Preenchimento: 0x{:02X}",
+                    self.original_len,
+                    self.data.len(),
+                    if self.data.len().is_power_of_two() { "" } else { " (não é potência de 2)" },
+                    self.default_fill
+                ));
+            }
+            if let Some(position) = self.position_status() {
+                lines.push(position);
+            }
+        } else {
+            lines.push("Nenhum arquivo aberto".to_string());
+        }
+
+        if let Some(ref reference) = self.reference {
+            let mut line = format!("Referência: {} bytes", reference.len());
+            if reference.len() != self.data.len() {
+                line += " (tamanho difere)";
+            }
+            lines.push(line);
+        }
+
+        // Painel do cabeçalho do cartucho
+        if self.show_header {
+            match self.parse_header() {
+                Some(header) => {
+                    let console = match header.console {
+                        Console::Gb => "GB/GBC",
+                        Console::Gba => "GBA",
+                    };
+                    let mut line = format!("Console: {} | Título: {} | Fabricante: {}", console, header.title, header.maker_code);
+                    if let Some(ref code) = header.game_code {
+                        line += &format!(" | Código: {}", code);
+                    }
+                    if let Some(cartridge_type) = header.cartridge_type {
+                        line += &format!(" | Cartucho: 0x{:02X}", cartridge_type);
+                    }
+                    line += &format!(" | ROM: {} KB", header.rom_size / 1024);
+                    lines.push(line);
+                    if header.console == Console::Gba {
+                        let problems = self.check_gba_header();
+                        if problems.is_empty() {
+                            lines.push("Cabeçalho GBA válido".to_string());
+                        } else {
+                            lines.push(format!("Cabeçalho GBA suspeito: {}", problems.join(", ")));
+                        }
+                    }
+                }
+                None => lines.push("Cabeçalho de cartucho não reconhecido".to_string()),
+            }
+        }
+
+        // Checksums do cabeçalho Game Boy: valor atual vs calculado
+        if self.is_gb_rom() {
+            if let (Some(header), Some(global)) = (self.gb_header_checksum(), self.gb_global_checksum()) {
+                let current_header = self.data[0x14D];
+                let current_global = u16::from_be_bytes([self.data[0x14E], self.data[0x14F]]);
+                let ok = current_header == header && current_global == global;
+                lines.push(format!(
+                    "Checksum: cabeçalho 0x{:02X} (calculado 0x{:02X}) | global 0x{:04X} (calculado 0x{:04X}){}",
+                    current_header, header, current_global, global,
+                    if ok { "" } else { " [DESSINCRONIZADO - K corrige]" }
+                ));
+            }
+        }
+        lines.push("-------------------------------------------------------------------------------".to_string());
+        
+        // Instruções
+        let key = |action| self.keymap.get(action);
+        lines.push(format!(
+            "Comandos: {} (ajuda com todos os atalhos), {} (abrir), {} (salvar), {} (ir para), {} (buscar), {} (sair)",
+            key(Action::Help),
+            key(Action::Open),
+            key(Action::Save),
+            key(Action::Goto),
+            key(Action::Search),
+            key(Action::Quit)
+        ));
+        lines.push("-------------------------------------------------------------------------------".to_string());
+        lines
+    }
+
+    // Linha da tela onde começa a grade de dados (cabeçalho + cabeçalho da tabela e régua dos registros)
+    fn grid_top(&self) -> usize {
+        self.header_lines().len() + 2 + usize::from(self.struct_size.is_some())
+    }
+
+    // Renderizar o conteúdo do editor para o terminal
+    fn render(&self) {
+        // Limpar tela
+        print!("\x1B[2J\x1B[1;1H");
+        
+        // Cabeçalho
+        for line in self.header_lines() {
+            println!("{}", line);
+        }
+        
+        if self.data.is_empty() {
+            println!("Nenhum dado para exibir. Use '{}' para abrir um arquivo.", self.keymap.get(Action::Open));
+            println!();
+            println!("{}", self.footer_line());
+            return;
+        }
+        
+        if let Some(split) = self.split {
+            self.render_split(split);
+        } else {
+            self.render_grid();
+        }
+        
+        // Inspetor de dados abaixo da grade
+        if let Some(offset) = self.get_cursor_offset() {
+            println!();
+            for line in self.inspect(offset).lines() {
+                println!("{}", line);
+            }
+        }
+        for line in self.template_lines() {
+            println!("{}", line);
+        }
+
+        // Rodapé reservado para mensagens de status e perguntas
+        println!();
+        println!("{}", self.footer_line());
+
+        if self.show_help {
+            self.render_help();
+        }
+    }
+
+    // Cores da grade; com a ajuda aberta, tudo fica esmaecido por trás dela
+    fn grid_theme(&self) -> Cow<'_, Theme> {
+        if self.show_help {
+            Cow::Owned(Theme::dimmed())
+        } else {
+            Cow::Borrowed(&self.theme)
+        }
+    }
+
+    // Ajuda desenhada por cima da grade, a partir da primeira linha dela
+    fn render_help(&self) {
+        let lines = help::lines(&self.keymap);
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        // Guardar e depois restaurar a posição do cursor do terminal, que fica no fim da tela
+        print!("\x1B7");
+        for (i, line) in lines.iter().enumerate() {
+            let text = format!(" {:<width$} ", line, width = width);
+            print!("\x1B[{};3H{}", self.grid_top() + 1 + i, Theme::paint("7", &text));
+        }
+        print!("\x1B8");
+        io::stdout().flush().unwrap();
+    }
+
+    // Endereço da linha, com "*" quando ela tem um marcador
+    fn gutter(&self, row_offset: usize) -> String {
+        let row_end = (row_offset + self.bytes_per_row).min(self.data.len());
+        let has_bookmark = (row_offset..row_end).any(|i| self.is_bookmarked(i));
+        format!(
+            "{}{}|",
+            Theme::paint(&self.grid_theme().gutter, &self.format_address(row_offset)),
+            if has_bookmark { "*" } else { " " }
+        )
+    }
+
+    // Régua com a posição de cada coluna dentro do registro, na primeira linha visível a partir de `view_offset`.
+    // Mostra "##" no início de cada registro e deixa em branco as colunas antes da tabela.
+    fn record_ruler(&self, view_offset: usize) -> Option<String> {
+        self.struct_size?;
+        let cells: String = (view_offset..view_offset + self.bytes_per_row)
+            .map(|offset| match self.record_at(offset) {
+                Some((start, _)) if start == offset => "## ".to_string(),
+                Some((start, _)) if offset - start <= 0xFF => format!("{:02X} ", offset - start),
+                Some(_) => "++ ".to_string(),
+                None => "   ".to_string(),
+            })
+            .collect();
+        Some(format!("Campo     | {}", cells))
+    }
+
+    // Bytes de uma linha em hexadecimal; `cursor` é o offset do cursor do painel e `focused` diz se é o painel ativo
+    fn hex_cells(&self, row_offset: usize, cursor: Option<usize>, focused: bool) -> String {
+        let row_end = (row_offset + self.bytes_per_row).min(self.data.len());
+        let selection = self.selection_range();
+        let theme = self.grid_theme();
+        let mut cells = String::new();
+        for i in row_offset..row_end {
+            let is_cursor_here = cursor == Some(i);
+            let (color, text) = match self.pending_nibble {
+                // Nibble parcialmente digitado em destaque
+                Some(nibble) if is_cursor_here && focused => (&theme.pending, format!("{:X}_", nibble)),
+                _ => {
+                    let color = if is_cursor_here && focused && !self.cursor_in_ascii {
+                        &theme.cursor
+                    } else if is_cursor_here {
+                        &theme.cursor_other // Cursor está no painel ASCII ou no painel sem foco
+                    } else if selection.is_some_and(|(start, end)| (start..=end).contains(&i)) {
+                        &theme.selection
+                    } else if self.is_bookmarked(i) {
+                        &theme.bookmark
+                    } else if self.differs(i) {
+                        &theme.diff
+                    } else if self.session_edits.contains(&i) {
+                        &theme.modified
+                    } else if self.is_locked(i) {
+                        &theme.locked
+                    } else if self.record_at(i).is_some_and(|(_, index)| index % 2 == 1) {
+                        &theme.record_alt
+                    } else {
+                        &theme.hex
+                    };
+                    (color, format!("{:02X}", self.data[i]))
+                }
+            };
+            cells += &format!(" {}", Theme::paint(color, &text));
+        }
+
+        // Preencher espaços vazios
+        for _ in row_end..row_offset + self.bytes_per_row {
+            cells += "   ";
+        }
+        cells
+    }
+
+    // Dois painéis hexadecimais lado a lado, cada um com a própria rolagem
+    fn render_split(&self, split: SplitView) {
+        let focused = (self.view_offset, self.get_cursor_offset(), true);
+        let other = (split.view_offset - split.view_offset % self.bytes_per_row, Some(split.cursor), false);
+        let panes = if split.focus_right { [other, focused] } else { [focused, other] };
+
+        let columns: String = (0..self.bytes_per_row).map(|i| format!("{:02X} ", i)).collect();
+        println!("Offset    | {} ‖ Offset    | {}", columns, columns);
+        if let [Some(left), Some(right)] = panes.map(|(view_offset, _, _)| self.record_ruler(view_offset)) {
+            println!("{} ‖ {}", left, right);
+        }
+        let rule = format!("-----------+{}", "-".repeat(self.bytes_per_row * 3));
+        println!("{} ‖ {}", rule, rule);
+
+        let pane_width = 12 + self.bytes_per_row * 3;
+        for display_row in 0..self.visible_rows() {
+            let rows = panes.map(|(view_offset, cursor, focused)| {
+                let row_offset = view_offset + display_row * self.bytes_per_row;
+                (row_offset < self.data.len())
+                    .then(|| format!("{}{}", self.gutter(row_offset), self.hex_cells(row_offset, cursor, focused)))
+            });
+            if rows.iter().all(Option::is_none) {
+                break;
+            }
+            let [left, right] = rows.map(|row| row.unwrap_or_else(|| " ".repeat(pane_width)));
+            println!("{} ‖ {}", left, right);
+        }
+    }
+
+    // Grade normal: painel hexadecimal, painel ASCII e barra de rolagem
+    fn render_grid(&self) {
+        // Cabeçalho da tabela
+        print!("Offset    | ");
+        for i in 0..self.bytes_per_row {
+            print!("{:02X} ", i);
+        }
+        println!("| ASCII");
+        if let Some(ruler) = self.record_ruler(self.view_offset) {
+            println!("{}|", ruler);
+        }
+        println!(
+            "-----------+{}+{}",
+            "-".repeat(self.bytes_per_row * 3 + 1),
+            "-".repeat(self.bytes_per_row * self.ascii_width() + 1)
+        );
+        
+        // Linhas de dados
+        let end_offset = std::cmp::min(
+            self.view_offset + self.visible_rows() * self.bytes_per_row,
+            self.data.len()
+        );
+        
+        let mut row_offset = self.view_offset;
+        let mut display_row = 0;
+        let (thumb_start, thumb_end) = self.scrollbar_thumb();
+        let cursor = self.get_cursor_offset();
+        let theme = self.grid_theme();
+        
+        while row_offset < end_offset {
+            let row_end = std::cmp::min(row_offset + self.bytes_per_row, self.data.len());
+            
+            print!("{}{}", self.gutter(row_offset), self.hex_cells(row_offset, cursor, true));
+            
+            // ASCII (ou a tabela de caracteres, se carregada)
+            print!(" | ");
+            for i in row_offset..row_end {
+                let byte = self.data[i];
+                let glyph = if let Some(ref table) = self.table {
+                    // Um caractere por byte para manter o painel alinhado
+                    table.get(&byte).and_then(|text| text.chars().next()).unwrap_or('·').to_string()
+                } else {
+                    self.ascii_mode.glyph(byte)
+                };
+
+                let is_cursor_here = self.cursor_pos == ((i - row_offset) * 3 + 10, display_row);
+                let color = if is_cursor_here && self.cursor_in_ascii {
+                    &theme.cursor
+                } else if is_cursor_here {
+                    &theme.cursor_other // Cursor está no painel hexadecimal
+                } else {
+                    &theme.ascii
+                };
+                print!("{}", Theme::paint(color, &glyph));
+            }
+
+            // Barra de rolagem na borda direita
+            for _ in row_end..row_offset + self.bytes_per_row {
+                print!("{}", " ".repeat(self.ascii_width()));
+            }
+            let in_thumb = (thumb_start..thumb_end).contains(&display_row);
+            println!(" {}", if in_thumb { '█' } else { '░' });
+            
+            row_offset += self.bytes_per_row;
+            display_row += 1;
+        }
+    }
+}
+
+// Pasta do usuário, onde ficam a configuração e o estado do editor
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from)
+}
+
+// Mensagem de falha ao abrir um arquivo, conforme a causa
+fn open_error_message(path: &Path, error: &io::Error) -> String {
+    let path = path.display();
+    match error.kind() {
+        io::ErrorKind::NotFound => format!("Arquivo não encontrado: {}", path),
+        io::ErrorKind::PermissionDenied => format!("Sem permissão para ler o arquivo: {}", path),
+        io::ErrorKind::IsADirectory => format!("O caminho é um diretório, não um arquivo: {}", path),
+        _ => format!("Erro ao abrir arquivo {}: {}", path, error),
+    }
+}
+
+// Caminho do arquivo a abrir pelo diálogo do sistema, quando compilado com o recurso "dialog";
+// sem diálogo (ou cancelado), o caminho é perguntado no rodapé
+fn dialog_open_path() -> Option<PathBuf> {
+    #[cfg(feature = "dialog")]
+    return rfd::FileDialog::new()
+        .add_filter("ROMs", &["gba", "gb", "gbc"])
+        .add_filter("Todos os arquivos", &["*"])
+        .pick_file();
+    #[cfg(not(feature = "dialog"))]
+    None
+}
+
+// Caminho para "salvar como", pelo diálogo do sistema quando disponível
+fn dialog_save_path() -> Option<PathBuf> {
+    #[cfg(feature = "dialog")]
+    return rfd::FileDialog::new().save_file();
+    #[cfg(not(feature = "dialog"))]
+    None
+}
+
+// Área de transferência do sistema, aberta no primeiro uso e mantida até o fim do programa
+// (no X11 o texto copiado só fica disponível enquanto ela existir)
+#[cfg(feature = "clipboard")]
+fn with_system_clipboard<T>(f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>) -> io::Result<T> {
+    thread_local! {
+        static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+    }
+    CLIPBOARD.with_borrow_mut(|clipboard| {
+        if clipboard.is_none() {
+            *clipboard = Some(arboard::Clipboard::new().map_err(|e| io::Error::other(e.to_string()))?);
+        }
+        f(clipboard.as_mut().unwrap()).map_err(|e| io::Error::other(e.to_string()))
+    })
+}
+
+// Texto da área de transferência do sistema, disponível quando compilado com o recurso "clipboard"
+fn system_clipboard_text() -> io::Result<String> {
+    #[cfg(feature = "clipboard")]
+    return with_system_clipboard(|clipboard| clipboard.get_text());
+    #[cfg(not(feature = "clipboard"))]
+    Err(clipboard_unsupported())
+}
+
+fn set_system_clipboard_text(text: String) -> io::Result<()> {
+    #[cfg(feature = "clipboard")]
+    return with_system_clipboard(|clipboard| clipboard.set_text(text));
+    #[cfg(not(feature = "clipboard"))]
+    {
+        let _ = text;
+        Err(clipboard_unsupported())
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn clipboard_unsupported() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "compilado sem o recurso \"clipboard\"")
+}
+
+fn read_only_error() -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, "modo somente leitura")
+}
+
+fn fixed_size_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "arquivo aberto com --stream não pode mudar de tamanho")
+}
+
+fn locked_error() -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, "intervalo bloqueado (Ctrl+Shift+L desbloqueia)")
+}
+
+fn stdin_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "os dados vieram da entrada padrão; use salvar como")
+}
+
+// Caminho de um arquivo auxiliar ao lado do original (ex: rom.gba.bak)
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+// Fila dos caracteres digitados na janela, usada pelo painel ASCII
+struct TypedChars(Rc<RefCell<Vec<char>>>);
+
+impl InputCallback for TypedChars {
+    fn add_char(&mut self, uni_char: u32) {
+        if let Some(c) = char::from_u32(uni_char) {
+            self.0.borrow_mut().push(c);
+        }
+    }
+}
+
+// Teclas de movimento mantidas pressionadas e quando cada uma deve repetir
+#[derive(Default)]
+struct HeldKeys(HashMap<Key, Instant>);
+
+impl HeldKeys {
+    // Verdadeiro quando a tecla é pressionada e, depois de REPEAT_DELAY, a cada REPEAT_INTERVAL enquanto continuar pressionada
+    fn fire(&mut self, window: &Window, key: Key) -> bool {
+        if !window.is_key_down(key) {
+            self.0.remove(&key);
+            return false;
+        }
+        let now = Instant::now();
+        match self.0.get_mut(&key) {
+            None => {
+                self.0.insert(key, now + REPEAT_DELAY);
+                true
+            }
+            // Contar a partir de agora evita uma rajada depois de um prompt que bloqueou o loop
+            Some(next) if now >= *next => {
+                *next = now + REPEAT_INTERVAL;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+}
+
+// Converter uma tecla em seu valor hexadecimal (0-9, A-F)
+fn key_to_nibble(key: Key) -> Option<u8> {
+    let nibble = match key {
+        Key::Key0 | Key::NumPad0 => 0x0,
+        Key::Key1 | Key::NumPad1 => 0x1,
+        Key::Key2 | Key::NumPad2 => 0x2,
+        Key::Key3 | Key::NumPad3 => 0x3,
+        Key::Key4 | Key::NumPad4 => 0x4,
+        Key::Key5 | Key::NumPad5 => 0x5,
+        Key::Key6 | Key::NumPad6 => 0x6,
+        Key::Key7 | Key::NumPad7 => 0x7,
+        Key::Key8 | Key::NumPad8 => 0x8,
+        Key::Key9 | Key::NumPad9 => 0x9,
+        Key::A => 0xA,
+        Key::B => 0xB,
+        Key::C => 0xC,
+        Key::D => 0xD,
+        Key::E => 0xE,
+        Key::F => 0xF,
+        _ => return None,
+    };
+    Some(nibble)
+}
+
+// Interpretar um número hexadecimal, com ou sem o prefixo 0x
+fn parse_hex(input: &str) -> Option<u64> {
+    let digits = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")).unwrap_or(input);
+    // from_str_radix aceitaria um "+" na frente
+    if digits.starts_with('+') {
+        return None;
+    }
+    u64::from_str_radix(digits, 16).ok()
+}
+
+// Interpretar um endereço hexadecimal, com ou sem o prefixo 0x
+fn parse_offset(input: &str) -> Option<usize> {
+    parse_hex(input).and_then(|value| usize::try_from(value).ok())
+}
+
+// Interpretar um intervalo "início fim" (ou "início-fim") de offsets hexadecimais
+fn parse_range(input: &str) -> Option<(usize, usize)> {
+    let mut parts = input.split(|c: char| c.is_whitespace() || c == '-').filter(|part| !part.is_empty());
+    let start = parse_offset(parts.next()?)?;
+    let end = parse_offset(parts.next()?)?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((start, end))
+}
+
+// Interpretar bytes hexadecimais separados por espaço (ex: "25 00 3C")
+fn parse_hex_bytes(input: &str) -> Option<Vec<u8>> {
+    input
+        .split_whitespace()
+        .map(|token| u8::from_str_radix(token, 16).ok())
+        .collect()
+}
+
+// Hex vindo de fora do editor: aceita vírgulas e prefixos 0x (ex: um array C exportado); vazio é inválido
+fn parse_clipboard_hex(text: &str) -> Option<Vec<u8>> {
+    let tokens = text.split(|c: char| c.is_whitespace() || c == ',').filter(|token| !token.is_empty());
+    let bytes: Option<Vec<u8>> = tokens
+        .map(|token| parse_hex(token).and_then(|value| u8::try_from(value).ok()))
+        .collect();
+    bytes.filter(|bytes| !bytes.is_empty())
+}
+
+// Como `parse_hex_bytes`, aceitando "??" como curinga (ex: "25 ?? 3C")
+fn parse_search_pattern(input: &str) -> Option<Vec<Option<u8>>> {
+    input
+        .split_whitespace()
+        .map(|token| match token {
+            "??" => Some(None),
+            _ => u8::from_str_radix(token, 16).ok().map(Some),
+        })
+        .collect()
+}
+
+fn main() {
+    let mut editor = HexEditor::new();
+
+    // Tema salvo em ~/.pokehex_theme; sem o arquivo fica o tema padrão
+    if let Some(text) = editor.theme_path().and_then(|path| fs::read_to_string(path).ok()) {
+        match theme::parse(&text) {
+            Ok(theme) => editor.theme = theme,
+            Err(e) => editor.set_status(format!("Erro ao carregar o tema: {}", e)),
+        }
+    }
+
+    // Atalhos salvos em ~/.pokehex_keys (linhas "ação=tecla", ex: "mover_cima=K")
+    if let Some(text) = editor.keys_path().and_then(|path| fs::read_to_string(path).ok()) {
+        let (keymap, warnings) = keymap::parse(&text);
+        editor.keymap = keymap;
+        if !warnings.is_empty() {
+            editor.set_status(format!("Atalhos com problemas (usando o padrão): {}", warnings.join("; ")));
+        }
+    }
+    
+    // Convenções de texto do jogo em ~/.pokehex_text (ex: "terminadores=50" e "controle=4F BR" na 1ª e 2ª geração)
+    if let Some(text) = editor.text_codes_path().and_then(|path| fs::read_to_string(path).ok()) {
+        let (codes, warnings) = table::parse_codes(&text);
+        editor.text_codes = codes;
+        if !warnings.is_empty() {
+            editor.set_status(format!("Convenções de texto com problemas: {}", warnings.join("; ")));
+        }
+    }
+
+    // Verificar argumentos de linha de comando
+    let mut file_arg: Option<PathBuf> = None;
+    let mut offset_arg = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--readonly" => editor.read_only = true,
+            "--mmap" => editor.use_mmap = true,
+            "--stream" => editor.use_stream = true,
+            "--offset" => offset_arg = args.next(),
+            // Segundos entre as cópias de recuperação; 0 desativa
+            "--autosave" => match args.next().and_then(|secs| secs.parse::<u64>().ok()) {
+                Some(0) => editor.autosave_interval = None,
+                Some(secs) => editor.autosave_interval = Some(Duration::from_secs(secs)),
+                None => editor.set_status("Uso: --autosave <segundos> (0 desativa)"),
+            },
+            // Milissegundos de cada fase do cursor piscando na janela; 0 deixa o cursor fixo
+            "--blink" => match args.next().and_then(|ms| ms.parse::<u64>().ok()) {
+                Some(0) => editor.cursor_blink = None,
+                Some(ms) => editor.cursor_blink = Some(Duration::from_millis(ms)),
+                None => editor.set_status("Uso: --blink <milissegundos> (0 desativa)"),
+            },
+            "--theme" => {
+                let name = args.next().unwrap_or_default();
+                match Theme::preset(&name) {
+                    Some(theme) => editor.theme = theme,
+                    None => editor.set_status(format!("Tema desconhecido: {} (use escuro, claro ou alto-contraste)", name)),
+                }
+            }
+            // Segundo argumento posicional: offset inicial
+            _ if file_arg.is_some() => offset_arg = Some(arg),
+            _ => file_arg = Some(PathBuf::from(arg)),
+        }
+    }
+    if let Some(path) = file_arg {
+        match editor.open_file(&path) {
+            Ok(_) => {
+                editor.set_status(format!("Arquivo aberto: {}", path.display()));
+                if let Some(input) = offset_arg {
+                    editor.goto_start_offset(&input);
+                }
+            }
+            Err(e) => editor.set_status(open_error_message(&path, &e)),
+        }
+    } else {
+        editor.restore_session();
+    }
+
+    // Uma cópia de recuperação mais nova que a ROM indica uma sessão encerrada sem salvar
+    if let Some(recovery) = editor.recovery_file() {
+        let prompt = format!("Há uma cópia de recuperação mais recente ({}). Restaurar? (s/N): ", recovery.display());
+        editor.start_prompt(prompt, PromptAction::Recovery(recovery));
+    }
+    
+    // Configurar janela
+    let mut window = Window::new(
+        "Editor Hexadecimal para ROMs de Pokémon",
+        WIDTH * CHAR_WIDTH, HEIGHT * CHAR_HEIGHT,  // Tamanho aproximado da janela
+        WindowOptions { resize: true, ..WindowOptions::default() },
+    )
+    .unwrap_or_else(|e| {
+        panic!("{}", e);
+    });
+    
+    // Limitar FPS para não consumir muita CPU
+    window.limit_update_rate(Some(std::time::Duration::from_millis(16)));
+    
+    let typed_chars = Rc::new(RefCell::new(Vec::new()));
+    window.set_input_callback(Box::new(TypedChars(Rc::clone(&typed_chars))));
+    
+    let mut mouse_was_down = false;
+    let mut held_keys = HeldKeys::default();
+    let keymap = editor.keymap.clone();
+    let mut window_size = window.get_size();
+    let mut status_visible = false;
+    let mut graphics: Option<Vec<u32>> = None;
+    let mut graphics_shown = false;
+    let mut blink_phase = 0;
+    
+    // Loop principal
+    while window.is_open() && !editor.quitting {
+        // Toda mudança de estado vem do teclado ou do mouse; além delas, a tela só muda quando a
+        // janela é redimensionada ou a mensagem do rodapé expira
+        let activity = !window.get_keys().is_empty()
+            || !window.get_keys_released().is_empty()
+            || !typed_chars.borrow().is_empty()
+            || window.get_mouse_down(MouseButton::Left) != mouse_was_down;
+        if activity || window.get_size() != window_size || editor.status_line().is_some() != status_visible {
+            editor.dirty = true;
+        }
+        window_size = window.get_size();
+        // Mais linhas de dados quando a janela cresce
+        editor.resize(window_size.1 / CHAR_HEIGHT);
+        status_visible = editor.status_line().is_some();
+
+        let ctrl = window.is_key_down(Key::LeftCtrl) || window.is_key_down(Key::RightCtrl);
+        let shift = window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift);
+        // No painel ASCII as teclas imprimíveis digitam texto em vez de acionar comandos
+        let plain = !ctrl && !editor.cursor_in_ascii;
+
+        if editor.show_help {
+            // Qualquer tecla fecha a ajuda sem acionar o próprio comando
+            if !window.get_keys_released().is_empty() {
+                editor.show_help = false;
+            }
+            typed_chars.borrow_mut().clear();
+            editor.redraw();
+            window.update();
+            continue;
+        }
+
+        if let Some(prompt) = editor.prompt.as_mut() {
+            // Com uma pergunta aberta, o teclado só edita a resposta; Enter confirma e Esc cancela
+            prompt.text.extend(typed_chars.borrow_mut().drain(..).filter(|c| !c.is_control()));
+            if held_keys.fire(&window, Key::Backspace) {
+                prompt.text.pop();
+            }
+            if window.is_key_released(Key::Escape) {
+                editor.prompt = None;
+            } else if window.is_key_released(Key::Enter) {
+                let Prompt { text, action, .. } = editor.prompt.take().unwrap();
+                editor.finish_prompt(action, &text);
+            }
+            editor.redraw();
+            window.update();
+            continue;
+        }
+
+        // Ações configuráveis; sem Ctrl, as teclas de texto não valem no painel ASCII
+        let in_ascii = editor.cursor_in_ascii;
+        let triggered = |action: Action| {
+            let binding = keymap.get(action);
+            binding.ctrl == ctrl
+                && binding.shift == shift
+                && !(in_ascii && binding.types_text())
+                && window.is_key_released(binding.key)
+        };
+        // Os comandos fixos ignoram as teclas tomadas por uma ação
+        let released = |key: Key| window.is_key_released(key) && !keymap.claims(key, ctrl, shift);
+
+        if triggered(Action::Help) {
+            editor.show_help = true;
+        }
+
+        if triggered(Action::Quit) {
+            // Sair, confirmando antes de perder alterações não salvas em qualquer aba
+            if !editor.any_modified() {
+                break;
+            }
+            editor.start_prompt("Há alterações não salvas. (S)alvar, (D)escartar ou (C)ancelar? ", PromptAction::Quit);
+        }
+
+        // Clique do mouse para posicionar o cursor
+        let mouse_down = window.get_mouse_down(MouseButton::Left);
+        if mouse_down && !mouse_was_down {
+            if let Some((mx, my)) = window.get_mouse_pos(MouseMode::Discard) {
+                let (mx, my) = (mx as usize, my as usize);
+                match (editor.minimap_offset(mx, my, window_size.0, window_size.1), editor.graphics_view) {
+                    // O clique no minimapa leva a visualização ao trecho
+                    (Some(offset), _) => {
+                        editor.push_nav();
+                        editor.goto_offset_top(offset);
+                        editor.set_status(format!("Minimapa: 0x{:08X}", offset));
+                    }
+                    // Na visão de tiles, o clique leva o cursor ao tile
+                    (None, Some(GraphicsView::Tiles)) => {
+                        if let Some(offset) = editor.tile_at(mx, my, window_size.0) {
+                            editor.goto_offset(offset);
+                            editor.set_status(format!("Tile em 0x{:08X}", offset));
+                        }
+                    }
+                    // Na de paleta, mostra a cor e copia o offset dela para a área de transferência do sistema
+                    (None, Some(GraphicsView::Palette)) => {
+                        if let Some(offset) = editor.swatch_at(mx, my, window_size.0) {
+                            let color = u16::from_le_bytes([editor.data[offset], editor.data[offset + 1]]);
+                            let copied = set_system_clipboard_text(format!("0x{:08X}", offset)).is_ok();
+                            editor.set_status(format!(
+                                "Cor em 0x{:08X}: 0x{:04X} = #{:06X}{}",
+                                offset,
+                                color,
+                                tiles::bgr555_to_rgb(color),
+                                if copied { " (offset copiado)" } else { "" }
+                            ));
+                        }
+                    }
+                    (None, None) => {
+                        editor.click_to_cursor(mx / CHAR_WIDTH, my / CHAR_HEIGHT);
+                    }
+                }
+            }
+        }
+        mouse_was_down = mouse_down;
+
+        // Entrada do teclado
+        if ctrl && released(Key::O) {
+            // Abrir arquivo de referência para comparação
+            editor.start_prompt("Digite o caminho do arquivo de referência: ", PromptAction::Reference);
+        }
+        
+        if ctrl && released(Key::N) {
+            // Ir para a próxima diferença
+            let from = editor.get_cursor_offset().map_or(0, |offset| offset + 1);
+            match editor.next_diff(from) {
+                Some(offset) => {
+                    editor.push_nav();
+                    editor.goto_offset(offset);
+                    editor.set_status(format!("Diferença em 0x{:08X}", offset));
+                }
+                None if editor.reference.is_none() => editor.set_status("Nenhuma referência carregada. Use Ctrl+O"),
+                None => editor.set_status("Nenhuma outra diferença"),
+            }
+        }
+        
+        if triggered(Action::Open) {
+            // Abrir arquivo
+            match dialog_open_path() {
+                Some(path) => editor.finish_prompt(PromptAction::Open, &path.to_string_lossy()),
+                None => editor.start_prompt("Digite o caminho do arquivo para abrir: ", PromptAction::Open),
+            }
+        }
+        
+        // Salvar arquivo; sem caminho definido, funciona como "salvar como"
+        let save_as = triggered(Action::SaveAs) || (triggered(Action::Save) && (editor.rom_path.is_none() || editor.is_stdin()));
+        // Alguns flashcarts só aceitam ROMs GBA com tamanho potência de 2, então perguntar antes de gravar outro tamanho
+        if triggered(Action::Save) || save_as {
+            if editor.is_valid_rom_size() || !editor.warn_rom_size {
+                editor.finish_save(save_as);
+            } else {
+                let prompt = format!(
+                    "ROM GBA com {} bytes, que não é potência de 2 (alguns flashcarts exigem). (P)reencher até {} bytes, (S)alvar assim, (I)gnorar nesta sessão ou (C)ancelar? ",
+                    editor.data.len(),
+                    editor.data.len().next_power_of_two()
+                );
+                editor.start_prompt(prompt, PromptAction::RomSize { save_as });
+            }
+        }
+        
+        if ctrl && released(Key::R) {
+            // Alternar modo somente leitura
+            editor.read_only = !editor.read_only;
+            editor.set_status(format!("Modo somente leitura {}", if editor.read_only { "ativado" } else { "desativado" }));
+        }
+        
+        // Instantâneos: Shift+S guarda os dados atuais com um nome, Shift+R volta a um deles
+        if plain && shift && released(Key::S) {
+            editor.start_prompt("Nome do instantâneo: ", PromptAction::Snapshot);
+        }
+        if plain && shift && released(Key::R) {
+            if editor.snapshots.is_empty() {
+                editor.set_status("Nenhum instantâneo guardado. Use Shift+S");
+            } else {
+                // A lista vai na própria pergunta, já que o terminal é redesenhado a cada quadro
+                let mut names: Vec<&String> = editor.snapshots.keys().collect();
+                names.sort();
+                let list: Vec<String> = names.iter().map(|name| format!("{} ({} bytes)", name, editor.snapshots[*name].len())).collect();
+                editor.start_prompt(format!("Restaurar o instantâneo [{}]: ", list.join(", ")), PromptAction::RestoreSnapshot);
+            }
+        }
+
+        if plain && !shift && released(Key::R) {
+            // Recarregar o arquivo do disco
+            if editor.modified {
+                editor.start_prompt("Descartar alterações não salvas? (s/N): ", PromptAction::Reload);
+            } else {
+                editor.finish_prompt(PromptAction::Reload, "s");
+            }
+        }
+        
+        if plain && !shift && released(Key::X) {
+            // Exportar dump hexadecimal em texto
+            editor.start_prompt("Exportar (V)isível ou (T)udo? ", PromptAction::DumpScope);
+        }
+        
+        if plain && shift && released(Key::X) {
+            // Exportar a seleção (ou um intervalo digitado) como bytes crus
+            match editor.selection_range() {
+                Some((start, end)) => editor.start_prompt("Digite o caminho do arquivo .bin: ", PromptAction::ExportBin(start, end)),
+                None => editor.start_prompt("Intervalo a exportar (hex, ex: 1000 101F): ", PromptAction::ExportRange),
+            }
+        }
+        
+        if plain && shift && released(Key::I) {
+            // Importar um arquivo .bin sobre os bytes a partir do cursor (ou inserido, no modo inserir)
+            if let Some(offset) = editor.get_cursor_offset() {
+                editor.start_prompt("Digite o caminho do arquivo .bin: ", PromptAction::Import(offset));
+            }
+        }
+        
+        if ctrl && shift && released(Key::E) {
+            // Editar o campo do modelo sob o cursor, no formato em que ele aparece no painel
+            match editor.get_cursor_offset().and_then(|offset| editor.template_field_at(offset)) {
+                Some(field) => {
+                    let prompt = format!(
+                        "{} ({}) em 0x{:08X} (atual: {}): ",
+                        field.name,
+                        field.kind.name(),
+                        field.offset,
+                        field.value.as_deref().unwrap_or("-")
+                    );
+                    editor.start_prompt(prompt, PromptAction::TemplateField(field.offset));
+                }
+                None if editor.template.is_none() => editor.set_status("Nenhum modelo aplicado (Ctrl+Shift+M)"),
+                None => editor.set_status("O cursor não está em um campo do modelo"),
+            }
+        }
+
+        if ctrl && !shift && released(Key::E) {
+            // Exportar a seleção (ou um intervalo digitado) como array C
+            match editor.selection_range() {
+                Some((start, end)) => editor.start_prompt("Nome do array: ", PromptAction::CArrayName(start, end)),
+                None => editor.start_prompt("Intervalo a exportar (hex, ex: 1000 101F): ", PromptAction::CArrayRange),
+            }
+        }
+        
+        if ctrl && shift && released(Key::P) {
+            // Aplicar um roteiro de edição em texto (set/fill/ptr, ver script.rs)
+            editor.start_prompt("Digite o caminho do roteiro: ", PromptAction::Script);
+        }
+
+        if ctrl && !shift && released(Key::P) {
+            // Aplicar patch IPS
+            editor.start_prompt("Digite o caminho do patch (.ips): ", PromptAction::ApplyIps);
+        }
+        
+        if ctrl && released(Key::G) {
+            // Gerar patch IPS a partir das alterações
+            editor.start_prompt("Digite o caminho do patch a gerar (.ips): ", PromptAction::CreateIps);
+        }
+        
+        if ctrl && shift && released(Key::L) {
+            // Bloquear a seleção contra edições, ou desbloqueá-la se ela já estiver toda bloqueada
+            let range = editor.selection_range().or_else(|| {
+                // Sem seleção, o cursor sobre um bloqueio desbloqueia o intervalo inteiro
+                let offset = editor.get_cursor_offset()?;
+                editor.locked_ranges.iter().copied().find(|&(start, end)| (start..=end).contains(&offset))
+            });
+            match range {
+                Some((start, end)) if editor.locked_ranges.iter().any(|&(a, b)| a <= start && end <= b) => {
+                    editor.unlock_range(start, end);
+                    editor.set_status(format!("Desbloqueado: 0x{:08X}-0x{:08X}", start, end));
+                }
+                Some((start, end)) => {
+                    editor.lock_range(start, end);
+                    editor.set_status(format!("Bloqueado contra edições: 0x{:08X}-0x{:08X}", start, end));
+                }
+                None => editor.set_status("Selecione o intervalo a bloquear"),
+            }
+        }
+
+        if ctrl && !shift && released(Key::L) {
+            // Reabrir um arquivo recente
+            let recent = editor.load_recent_files();
+            if recent.is_empty() {
+                editor.set_status("Nenhum arquivo recente");
+            } else {
+                // Só o nome de cada arquivo, para a lista caber no rodapé
+                let list: Vec<String> = recent
+                    .iter()
+                    .enumerate()
+                    .map(|(i, path)| {
+                        let name = path.file_name().map_or(path.display().to_string(), |name| name.to_string_lossy().into_owned());
+                        format!("{}. {}", i + 1, name)
+                    })
+                    .collect();
+                editor.start_prompt(format!("Número do arquivo para abrir [{}]: ", list.join(", ")), PromptAction::Recent);
+            }
+        }
+        
+        if plain && shift && released(Key::L) {
+            // Descompactar o bloco LZ77 (cabeçalho 0x10) que começa no cursor
+            if let Some(offset) = editor.get_cursor_offset() {
+                match lz77::decompress(&editor.data.slice(offset..editor.data.len()), 0) {
+                    Some(bytes) => {
+                        let prompt = format!(
+                            "LZ77 em 0x{:08X}: {} bytes descompactados. (A)brir em nova aba, (S)alvar em arquivo, (C)ompactar outros dados no lugar ou Enter para fechar? ",
+                            offset,
+                            bytes.len()
+                        );
+                        editor.start_prompt(prompt, PromptAction::Lz77(offset, bytes));
+                    }
+                    None => editor.set_status(format!("Não há um bloco LZ77 válido em 0x{:08X}", offset)),
+                }
+            }
+        }
+
+        if plain && !shift && released(Key::L) {
+            // Carregar tabela de caracteres
+            editor.start_prompt("Digite o caminho da tabela (.tbl): ", PromptAction::Table);
+        }
+        
+        // Bytes por linha
+        // +/- ajustam o byte sob o cursor ("+" é Shift+= no teclado principal)
+        if plain && (released(Key::Equal) || released(Key::NumPadPlus)) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                editor.increment_byte(offset);
+            }
+        }
+        if plain && (released(Key::Minus) || released(Key::NumPadMinus)) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                editor.decrement_byte(offset);
+            }
+        }
+
+        if plain && !shift && released(Key::Comma) {
+            editor.set_bytes_per_row(editor.bytes_per_row / 2);
+        }
+        if plain && !shift && released(Key::Period) {
+            editor.set_bytes_per_row(editor.bytes_per_row * 2);
+        }
+
+        // Visão de gráficos: Shift+T liga os tiles e Shift+V a paleta, < e > mudam os tiles por linha,
+        // Shift+P usa a paleta do cursor nos tiles
+        for (key, view, name) in [
+            (Key::T, GraphicsView::Tiles, "tiles 4bpp"),
+            (Key::V, GraphicsView::Palette, "cores BGR555"),
+        ] {
+            if plain && shift && released(key) {
+                if editor.graphics_view == Some(view) {
+                    editor.graphics_view = None;
+                    editor.set_status("Visão de gráficos desativada");
+                } else {
+                    editor.graphics_view = Some(view);
+                    editor.set_status(format!("Visão de gráficos: {} a partir do cursor", name));
+                }
+            }
+        }
+        if plain && shift && released(Key::M) {
+            // Minimapa da entropia na coluna da direita da janela; o clique leva ao trecho
+            editor.show_minimap = !editor.show_minimap;
+            editor.set_status(if editor.show_minimap { "Minimapa ligado" } else { "Minimapa desligado" });
+        }
+        if plain && shift && editor.graphics_view == Some(GraphicsView::Tiles) && (released(Key::Comma) || released(Key::Period)) {
+            let wider = released(Key::Period);
+            editor.tiles_wide = if wider { (editor.tiles_wide + 1).min(64) } else { (editor.tiles_wide - 1).max(1) };
+            editor.set_status(format!("{} tiles por linha", editor.tiles_wide));
+        }
+        if plain && shift && released(Key::P) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                let current = editor.tile_palette.map(|(at, _)| at);
+                if current == Some(offset) {
+                    editor.tile_palette = None;
+                    editor.set_status("Paleta dos tiles: tons de cinza");
+                } else {
+                    match tiles::palette(&editor.data.slice(offset..editor.data.len().min(offset + 32))) {
+                        Some(colors) => {
+                            editor.tile_palette = Some((offset, colors));
+                            editor.set_status(format!("Paleta dos tiles: 16 cores BGR555 de 0x{:08X}", offset));
+                        }
+                        None => editor.set_status("Não há 32 bytes de paleta a partir do cursor"),
+                    }
+                }
+            }
+        }
+        
+        if plain && !shift && released(Key::I) {
+            // Alternar painel do cabeçalho do cartucho
+            editor.show_header = !editor.show_header;
+        }
+        
+        if plain && !shift && released(Key::K) {
+            // Corrigir checksums do cabeçalho Game Boy
+            if editor.fix_gb_checksums() {
+                editor.set_status("Checksums corrigidos");
+            }
+        }
+        
+        if triggered(Action::Goto) {
+            // Ir para offset
+            let prompt = editor.goto_prompt();
+            editor.start_prompt(prompt, PromptAction::Goto);
+        }
+        
+        if plain && released(Key::H) {
+            if shift {
+                // Voltar aos endereços absolutos
+                editor.base_offset = None;
+                editor.set_status("Endereços absolutos");
+            } else if let Some(offset) = editor.get_cursor_offset() {
+                // Endereços relativos ao byte sob o cursor
+                editor.base_offset = Some(offset);
+                editor.set_status(format!("Base dos endereços: 0x{:08X}", offset));
+            }
+        }
+
+        if triggered(Action::Search) {
+            // Buscar sequência de bytes
+            editor.start_prompt("Buscar bytes (hex, ?? para qualquer byte, ex: 25 ?? 3C): ", PromptAction::SearchBytes);
+        }
+        
+        if ctrl && released(Key::H) && editor.writable() {
+            // Substituir uma sequência de bytes em todo o arquivo
+            editor.start_prompt("Buscar bytes (hex, ex: 25 00 3C): ", PromptAction::ReplaceFind);
+        }
+
+        if ctrl && released(Key::U) {
+            // Contar as ocorrências de um byte ou sequência no arquivo inteiro
+            editor.start_prompt("Contar bytes (hex, ex: FF ou 25 00): ", PromptAction::Count);
+        }
+
+        if triggered(Action::SearchText) {
+            // Buscar texto ASCII
+            editor.start_prompt("Buscar texto: ", PromptAction::SearchText);
+        }
+        
+        if ctrl && released(Key::T) {
+            // Alternar diferenciação de maiúsculas na busca de texto
+            editor.ignore_case = !editor.ignore_case;
+            if editor.ignore_case {
+                editor.set_status("Busca de texto sem diferenciar maiúsculas");
+            } else {
+                editor.set_status("Busca de texto diferenciando maiúsculas");
+            }
+        }
+        
+        if triggered(Action::FindNext) {
+            // Buscar próximo
+            if editor.last_search.is_none() {
+                editor.set_status(format!("Nenhuma busca anterior. Use '{}' para buscar", keymap.get(Action::Search)));
+            } else {
+                match editor.find_next() {
+                    Some(offset) => editor.set_status(format!("Padrão encontrado em 0x{:08X}", offset)),
+                    None => editor.set_status("Padrão não encontrado"),
+                }
+            }
+        }
+        
+        if ctrl && shift && released(Key::M) {
+            // Carregar um modelo de estrutura (ver template.rs) e aplicá-lo no cursor ou no offset do modelo
+            let prompt = if editor.template.is_some() {
+                "Caminho do modelo (vazio remove o atual): "
+            } else {
+                "Digite o caminho do modelo: "
+            };
+            editor.start_prompt(prompt, PromptAction::Template);
+        }
+
+        if ctrl && !shift && released(Key::M) && editor.writable() && editor.resizable() {
+            // Corrigir o tamanho do arquivo depois de inserções e remoções
+            let prompt = format!(
+                "Tamanho: {} bytes (original: {}). (P)reencher com 0x{:02X} até potência de 2, (T)runcar para o original, (E)xpandir até um tamanho padrão ou (A)parar o preenchimento final? ",
+                editor.data.len(),
+                editor.original_len,
+                editor.default_fill
+            );
+            editor.start_prompt(prompt, PromptAction::Resize);
+        }
+
+        // Marcadores
+        if plain && !shift && released(Key::M) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                editor.start_prompt(format!("Rótulo do marcador em 0x{:08X}: ", offset), PromptAction::Bookmark(offset));
+            }
+        }
+        if plain && shift && (released(Key::J) || released(Key::K)) {
+            // Pular para o próximo (Shift+J) ou o anterior (Shift+K) terminador de texto
+            if let Some(offset) = editor.get_cursor_offset() {
+                let forward = released(Key::J);
+                let terminators = editor.text_codes.terminators.iter();
+                let found = if forward {
+                    terminators.filter_map(|&byte| editor.find_byte(byte, offset + 1)).min()
+                } else {
+                    terminators.filter_map(|&byte| editor.rfind_byte(byte, offset)).max()
+                };
+                match found {
+                    Some(found) => {
+                        editor.push_nav();
+                        editor.goto_offset(found);
+                        editor.set_status(format!("Terminador 0x{:02X} em 0x{:08X}", editor.data[found], found));
+                    }
+                    None => editor.set_status(format!(
+                        "Nenhum terminador {} {} do cursor",
+                        editor.text_codes.terminators_label(),
+                        if forward { "depois" } else { "antes" }
+                    )),
+                }
+            }
+        }
+
+        if ctrl && shift && released(Key::J) {
+            // Definir os bytes terminadores dos textos; o primeiro é o gravado ao editar um texto
+            let prompt = format!("Terminadores dos textos em hex, ex: 50 FF (atual: {}): ", editor.text_codes.terminators_label());
+            editor.start_prompt(prompt, PromptAction::Terminators);
+        }
+
+        if plain && !shift && released(Key::J) {
+            match editor.next_bookmark() {
+                Some((offset, label)) => editor.set_status(format!("Marcador 0x{:08X}: {}", offset, label)),
+                None => editor.set_status("Nenhum marcador. Use 'M' para adicionar"),
+            }
+        }
+        
+        if ctrl && !shift && released(Key::J) {
+            // Ir para uma estrutura conhecida do jogo detectado
+            match editor.detect_game() {
+                Some(game) => {
+                    let known = game.known_offsets();
+                    if known.is_empty() {
+                        editor.set_status(format!("Nenhuma estrutura conhecida para {}", game.name()));
+                    } else {
+                        let list: Vec<String> = known
+                            .iter()
+                            .enumerate()
+                            .map(|(i, (label, offset))| format!("{}. {} 0x{:08X}", i + 1, label, offset))
+                            .collect();
+                        editor.start_prompt(format!("Estrutura de {} [{}]: ", game.name(), list.join(", ")), PromptAction::KnownOffset);
+                    }
+                }
+                None => editor.set_status("Jogo não reconhecido"),
+            }
+        }
+
+        // Ponteiros GBA
+        if plain && !shift && released(Key::P) {
+            match editor.jump_to_pointer() {
+                Some(target) => editor.set_status(format!("Ponteiro seguido para 0x{:08X}", target)),
+                None => editor.set_status("Não há um ponteiro GBA válido sob o cursor"),
+            }
+        }
+        if released(Key::Backspace) {
+            match editor.jump_back() {
+                Some(offset) => editor.set_status(format!("De volta a 0x{:08X}", offset)),
+                None => editor.set_status("Nenhum ponteiro seguido para voltar"),
+            }
+        }
+
+        // Histórico de navegação, como no navegador: Ctrl+[ volta ao lugar de antes do último salto, Ctrl+] avança
+        if ctrl && (released(Key::LeftBracket) || released(Key::RightBracket)) {
+            let back = released(Key::LeftBracket);
+            let restored = if back { editor.nav_back() } else { editor.nav_forward() };
+            match restored {
+                Some(offset) => editor.set_status(format!(
+                    "{} 0x{:08X} ({} para trás, {} para frente)",
+                    if back { "Voltou para" } else { "Avançou para" },
+                    offset,
+                    editor.nav_back.len(),
+                    editor.nav_forward.len()
+                )),
+                None if back => editor.set_status("Nenhuma posição anterior no histórico"),
+                None => editor.set_status("Nenhuma posição seguinte no histórico"),
+            }
+        }
+
+        if plain && shift && released(Key::N) {
+            // Editar o texto sob o cursor pela tabela de caracteres, até o terminador
+            match (editor.get_cursor_offset(), editor.table.is_some()) {
+                (_, false) => editor.set_status("Carregue uma tabela .tbl (L) para editar textos"),
+                (None, _) => {}
+                (Some(offset), true) => {
+                    let (current, old_len) = editor.decode_string(offset);
+                    let prompt = format!("Texto em 0x{:08X} ({} bytes: \"{}\"): ", offset, old_len, current);
+                    editor.start_prompt(prompt, PromptAction::EditText(offset, old_len));
+                }
+            }
+        }
+
+        if plain && shift && released(Key::W) {
+            // Editar em decimal o inteiro sob o cursor; "valor largura" também muda a largura (1, 2 ou 4 bytes)
+            if let Some(offset) = editor.get_cursor_offset() {
+                let endian = editor.endianness;
+                let current = editor
+                    .read_int(offset, editor.int_width, endian)
+                    .map_or("-".to_string(), |value| value.to_string());
+                let prompt = format!(
+                    "Valor decimal para {} bytes {} em 0x{:08X} (atual: {}): ",
+                    editor.int_width,
+                    endian.name(),
+                    offset,
+                    current
+                );
+                editor.start_prompt(prompt, PromptAction::WriteInt(offset));
+            }
+        }
+
+        if plain && !shift && released(Key::W) {
+            // Escrever sob o cursor um ponteiro para outro offset (ex: o espaço livre encontrado com U)
+            if let Some(offset) = editor.get_cursor_offset() {
+                let prompt = format!("Escrever em 0x{:08X} ponteiro para o offset (hex): ", offset);
+                editor.start_prompt(prompt, PromptAction::WritePointer(offset));
+            }
+        }
+
+        if plain && released(Key::U) {
+            // Procurar espaço livre depois do cursor, para mover dados para lá
+            let prompt = format!("Espaço livre: tamanho mínimo em bytes [byte 0x{:02X}, ex: 256 ou 256 00]: ", editor.free_byte);
+            editor.start_prompt(prompt, PromptAction::FreeSpace);
+        }
+        
+        // Movimentação do cursor (com Shift estende a seleção)
+        // Valem com qualquer modificador; teclas de texto não movem no painel ASCII
+        let movement = |action: Action| {
+            let binding = keymap.get(action);
+            (!(in_ascii && !ctrl && binding.types_text())).then_some(binding.key)
+        };
+        let arrows = [
+            (Action::Up, 0, -1),
+            (Action::Down, 0, 1),
+            (Action::Left, -3, 0),  // 3 caracteres por byte (2 dígitos + espaço)
+            (Action::Right, 3, 0),
+        ];
+        for (action, dx, dy) in arrows {
+            if movement(action).is_some_and(|key| held_keys.fire(&window, key)) {
+                if ctrl && dx != 0 {
+                    // Ctrl+Esquerda/Direita avançam de passo em passo
+                    editor.move_cursor_by_stride(dx / 3);
+                } else if ctrl && editor.struct_size.is_some() {
+                    // Ctrl+Cima/Baixo andam de registro em registro
+                    if let Some(index) = editor.step_record(dy) {
+                        editor.set_status(format!("Registro {}", index));
+                    }
+                } else if shift {
+                    editor.extend_selection(dx, dy);
+                } else {
+                    editor.move_cursor(dx, dy);
+                }
+            }
+        }
+        
+        if released(Key::Escape) {
+            editor.selection = None;
+        }
+        
+        // Rolagem
+        let page = editor.visible_rows() as isize;
+        if movement(Action::PageUp).is_some_and(|key| held_keys.fire(&window, key)) {
+            editor.scroll(-page);
+        }
+        if movement(Action::PageDown).is_some_and(|key| held_keys.fire(&window, key)) {
+            editor.scroll(page);
+        }
+        if movement(Action::Start).is_some_and(|key| window.is_key_released(key)) {
+            editor.goto_start();
+        }
+        if movement(Action::End).is_some_and(|key| window.is_key_released(key)) {
+            editor.goto_end();
+        }
+        
+        // Edição de bytes
+        if triggered(Action::EditByte) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                let current_value = editor.data[offset];
+                let prompt = format!("Editar byte em 0x{:08X} [valor atual: 0x{:02X}]: 0x", offset, current_value);
+                editor.start_prompt(prompt, PromptAction::EditByte(offset));
+            }
+        }
+        
+        // Digitação direta sobre o cursor: texto no painel ASCII, nibbles no hexadecimal
+        let typed: Vec<char> = typed_chars.borrow_mut().drain(..).collect();
+        if editor.cursor_in_ascii && !ctrl {
+            for c in typed {
+                if (' '..='~').contains(&c) {
+                    editor.type_ascii(c as u8);
+                }
+            }
+        } else if !ctrl {
+            for key in window.get_keys_pressed(KeyRepeat::No) {
+                // Uma tecla com ação configurada não digita
+                let Some(nibble) = key_to_nibble(key).filter(|_| !keymap.claims(key, ctrl, shift)) else {
+                    continue;
+                };
+                if shift && nibble < 10 {
+                    // Shift+0-7 inverte o bit correspondente do byte sob o cursor
+                    if let (Some(offset), true) = (editor.get_cursor_offset(), nibble < 8) {
+                        editor.toggle_bit(offset, nibble);
+                        editor.set_status(format!("Bit {} de 0x{:08X}: {}", nibble, offset, (editor.data[offset] >> nibble) & 1));
+                    }
+                } else {
+                    editor.type_nibble(nibble);
+                }
+            }
+        }
+        
+        if !ctrl && released(Key::Tab) {
+            // Na visão dividida, Tab alterna o painel com foco
+            if editor.split.is_some() {
+                editor.switch_split_focus();
+            } else {
+                editor.toggle_pane();
+            }
+        }
+
+        if plain && !shift && released(Key::V) {
+            // Comparar dois trechos do arquivo lado a lado
+            editor.toggle_split();
+        }
+
+        // Abas: Ctrl+Tab avança, Ctrl+1-9 escolhe, Ctrl+W fecha. Com Shift, os números vão aos marcadores.
+        if ctrl && released(Key::Tab) {
+            editor.switch_tab((editor.active_tab + 1) % editor.tabs.len());
+        }
+        let tab_keys = [Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5, Key::Key6, Key::Key7, Key::Key8, Key::Key9];
+        for (i, key) in tab_keys.into_iter().enumerate() {
+            if ctrl && !shift && released(key) {
+                editor.switch_tab(i);
+            }
+            if ctrl && shift && released(key) {
+                match editor.goto_bookmark(i) {
+                    Some((offset, label)) => editor.set_status(format!("Marcador {} em 0x{:08X}: {}", i + 1, offset, label)),
+                    None => editor.set_status(format!("Marcador {} não existe ({} marcadores)", i + 1, editor.bookmarks.len())),
+                }
+            }
+        }
+        if ctrl && released(Key::W) {
+            if editor.modified {
+                editor.start_prompt("A aba tem alterações não salvas. (S)alvar, (D)escartar ou (C)ancelar? ", PromptAction::CloseTab);
+            } else {
+                editor.close_tab();
+            }
+        }
+        
+        // Marcar início/fim da seleção
+        if plain && released(Key::LeftBracket) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                editor.selection = Some((offset, offset));
+            }
+        }
+        if plain && released(Key::RightBracket) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                let start = editor.selection.map_or(offset, |(start, _)| start);
+                editor.selection = Some((start, offset));
+            }
+        }
+        
+        if ctrl && released(Key::F) {
+            // Preencher a seleção com um byte
+            match editor.selection_range() {
+                Some((start, end)) => {
+                    let prompt = format!("Preencher 0x{:08X}-0x{:08X} com o byte: 0x", start, end);
+                    editor.start_prompt(prompt, PromptAction::Fill(start, end));
+                }
+                None => editor.set_status("Nenhuma seleção. Marque o início com '[' e o fim com ']'"),
+            }
+        }
+        
+        if ctrl && released(Key::D) {
+            // Definir o passo de Ctrl+Esquerda/Direita
+            editor.start_prompt(format!("Passo em bytes (atual: {}): ", editor.stride), PromptAction::Stride);
+        }
+
+        if plain && shift && released(Key::Q) {
+            // Ler o valor sob o cursor como número de espécie e de golpe do jogo detectado (u16 no GBA, byte nos de GB)
+            match (editor.get_cursor_offset(), editor.detect_game()) {
+                (Some(offset), Some(game)) => {
+                    let width = if editor.console() == Some(Console::Gba) { 2 } else { 1 };
+                    match editor.read_int(offset, width, Endian::Little) {
+                        Some(value) => {
+                            let index = value as u16;
+                            let name = |name: Option<String>| name.unwrap_or_else(|| "-".to_string());
+                            editor.set_status(format!(
+                                "{} {} em 0x{:08X}: espécie {} | golpe {}{}",
+                                game.name(),
+                                index,
+                                offset,
+                                name(editor.lookup_species(index)),
+                                name(editor.lookup_move(index)),
+                                if editor.table.is_none() { " (sem tabela: L carrega uma .tbl)" } else { "" }
+                            ));
+                        }
+                        None => editor.set_status(format!("Não há {} bytes no cursor", width)),
+                    }
+                }
+                (Some(_), None) => editor.set_status("Jogo não reconhecido; não há tabela de nomes"),
+                (None, _) => {}
+            }
+        }
+
+        if ctrl && released(Key::Q) {
+            // Definir a tabela de registros: a seleção vira o primeiro registro, ou "tamanho [início em hex]"
+            if let Some((start, end)) = editor.selection_range() {
+                editor.struct_size = Some(end - start + 1);
+                editor.struct_base = start;
+                editor.selection = None;
+                editor.report_records();
+            } else {
+                let current = editor.struct_size.map_or("nenhum".to_string(), |size| size.to_string());
+                let prompt = format!("Tamanho do registro e início em hex (atual: {}, 0 desativa): ", current);
+                editor.start_prompt(prompt, PromptAction::RecordSize);
+            }
+        }
+
+        if ctrl && released(Key::A) {
+            // Alternar a exibição dos bytes não imprimíveis no painel ASCII
+            editor.ascii_mode = editor.ascii_mode.next();
+            editor.set_status(format!("Painel ASCII: {}", editor.ascii_mode.name()));
+        }
+
+        if ctrl && released(Key::I) {
+            // Alternar a ordem dos bytes do inspetor
+            editor.endianness = editor.endianness.toggled();
+        }
+
+        if ctrl && released(Key::X) {
+            // Alternar os endereços entre hexadecimal e decimal
+            editor.address_base = editor.address_base.toggled();
+            editor.set_status(format!("Endereços em {}", editor.address_base.name()));
+        }
+
+        if ctrl && released(Key::K) {
+            // Limpar o destaque dos bytes editados (por exemplo, depois de salvar)
+            editor.session_edits.clear();
+            editor.set_status("Destaque das edições limpo");
+        }
+
+        if ctrl && released(Key::B) {
+            // Operação bit a bit na seleção ou, sem seleção, no byte sob o cursor
+            let range = editor
+                .selection_range()
+                .or_else(|| editor.get_cursor_offset().map(|offset| (offset, offset)));
+            if let Some((start, end)) = range {
+                editor.start_prompt("Operação (A)ND, (O)R, (X)OR ou (N)OT: ", PromptAction::Bitwise(start, end));
+            }
+        }
+
+        // Copiar e colar
+        if ctrl && !shift && released(Key::C) {
+            match editor.copy() {
+                0 => editor.set_status("Nenhuma seleção para copiar"),
+                count => editor.set_status(format!("{} bytes copiados", count)),
+            }
+        }
+        if ctrl && !shift && released(Key::V) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                let count = editor.paste(offset);
+                if count > 0 {
+                    editor.set_status(format!("{} bytes colados em 0x{:08X}", count, offset));
+                }
+            }
+        }
+        // Com Shift, a área de transferência do sistema (texto hex)
+        if ctrl && shift && released(Key::C) {
+            match editor.copy_to_system_clipboard() {
+                Ok(count) => editor.set_status(format!("{} bytes copiados como hex para o sistema", count)),
+                Err(e) => editor.set_status(format!("Não foi possível copiar: {}", e)),
+            }
+        }
+        if ctrl && shift && released(Key::V) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                match editor.paste_from_system_clipboard(offset) {
+                    Ok(0) => {}
+                    Ok(count) => editor.set_status(format!("{} bytes colados do sistema em 0x{:08X}", count, offset)),
+                    Err(e) => editor.set_status(format!("Não foi possível colar: {}", e)),
+                }
+            }
+        }
+        
+        // Inserção e remoção de bytes: Insert alterna o modo, Shift+Insert insere o byte de preenchimento
+        if !shift && released(Key::Insert) {
+            editor.edit_mode = editor.edit_mode.toggled();
+            editor.set_status(format!("Modo de edição: {}", editor.edit_mode.name()));
+        }
+        if shift && released(Key::Insert) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                let fill = editor.default_fill;
+                if editor.insert_byte(offset, fill) {
+                    editor.goto_offset(offset);
+                    editor.set_status(format!("Byte 0x{:02X} inserido em 0x{:08X}", fill, offset));
+                }
+            }
+        }
+
+        if released(Key::Delete) {
+            if let Some(offset) = editor.get_cursor_offset() {
+                if editor.delete_byte(offset) {
+                    editor.goto_offset(offset);
+                    editor.set_status(format!("Byte removido de 0x{:08X}", offset));
+                }
+            }
+        }
+
+        if plain && released(Key::Y) {
+            // Hashes da seleção ou do arquivo inteiro, para comparar com bancos de dados de ROMs
+            let range = editor.selection_range();
+            let sums = editor.checksum(range);
+            let scope = match range {
+                Some((start, end)) => format!("Seleção 0x{:08X}-0x{:08X}", start, end),
+                None => "Arquivo".to_string(),
+            };
+            editor.set_status(format!("{}: CRC32 {:08X} | MD5 {}", scope, sums.crc32, sums.md5_hex()));
+        }
+
+        if plain && released(Key::Z) {
+            // Byte usado ao inserir e ao completar o arquivo
+            let prompt = format!("Byte de preenchimento (hex) [atual: 0x{:02X}]: 0x", editor.default_fill);
+            editor.start_prompt(prompt, PromptAction::DefaultFill);
+        }
+        
+        // Desfazer/refazer
+        if triggered(Action::Undo) {
+            match editor.undo() {
+                Some(offset) => editor.set_status(format!("Desfeita alteração em 0x{:08X}", offset)),
+                None => editor.set_status("Nada para desfazer"),
+            }
+        }
+        if triggered(Action::Redo) {
+            match editor.redo() {
+                Some(offset) => editor.set_status(format!("Refeita alteração em 0x{:08X}", offset)),
+                None => editor.set_status("Nada para refazer"),
+            }
+        }
+        
+        // Cópias de recuperação periódicas; só uma falha é avisada, para não poluir o rodapé
+        if editor.autosave_due() {
+            if let Err(e) = editor.autosave() {
+                editor.set_status(format!("Erro ao gravar a cópia de recuperação: {}", e));
+            }
+        }
+
+        // Renderizar a interface; o quadro dos gráficos só é refeito quando algo mudou ou o cursor piscou.
+        // O pisca não marca a tela como suja: redesenhar o terminal a cada fase faria ele tremer.
+        let phase = editor.blink_phase();
+        if editor.dirty || phase != blink_phase {
+            graphics = editor.graphics_frame(window_size.0, window_size.1);
+            blink_phase = phase;
+        }
+        editor.redraw();
+        
+        // Atualizar a janela, limpando-a uma vez ao sair da visão de gráficos
+        let (width, height) = window_size;
+        let result = match graphics {
+            Some(ref frame) => window.update_with_buffer(frame, width, height),
+            None if graphics_shown => window.update_with_buffer(&vec![0; width * height], width, height),
+            None => {
+                window.update();
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            editor.set_status(format!("Erro ao desenhar os gráficos: {}", e));
+        }
+        graphics_shown = graphics.is_some();
+    }
+
+    // Assim como os recentes, a sessão é só uma conveniência; uma falha ao gravá-la não impede a saída
+    let _ = editor.save_session();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Diretório temporário próprio do teste
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pokehex_tests_{}", std::process::id())).join(name);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // Editor que guarda os recentes e a sessão numa pasta temporária, não na do usuário
+    fn new_editor() -> HexEditor {
+        let mut editor = HexEditor::new();
+        editor.config_dir = Some(temp_dir("config"));
+        editor
+    }
+
+    // Editor com os bytes em memória, como se tivessem sido lidos de `path`
+    fn editor_with(data: Vec<u8>, path: &str) -> HexEditor {
+        let mut editor = new_editor();
+        editor.load_data(Buffer::from(data));
+        editor.rom_path = Some(PathBuf::from(path));
+        editor
+    }
+
+    #[test]
+    fn gb_checksums_only_written_on_gb_roms() {
+        let mut gba = editor_with(vec![0x11; 0x200], "rom.gba");
+        assert!(!gba.fix_gb_checksums());
+        assert_eq!(gba.data.slice(0x14D..0x150).to_vec(), vec![0x11; 3]);
+
+        let mut gb = editor_with(vec![0x11; 0x200], "rom.gb");
+        gb.lock_range(0x14E, 0x14E);
+        assert!(!gb.fix_gb_checksums());
+        assert_eq!(gb.data.slice(0x14D..0x150).to_vec(), vec![0x11; 3]);
+
+        gb.unlock_range(0x14E, 0x14E);
+        assert!(gb.fix_gb_checksums());
+        assert_eq!(gb.data[0x14D], gb.gb_header_checksum().unwrap());
+        assert_eq!(u16::from_be_bytes([gb.data[0x14E], gb.data[0x14F]]), gb.gb_global_checksum().unwrap());
+    }
+
+    #[test]
+    fn locked_ranges_refuse_whole_operations() {
+        let mut editor = editor_with(vec![0u8; 0x40], "dados.bin");
+        editor.lock_range(0x10, 0x1F);
+        assert!(!editor.fill_range(0x08, 0x18, 0xAA));
+        assert!(!editor.apply_bitwise((0x00, 0x3F), BitOp::Or, 0x01));
+        editor.clipboard = vec![1; 0x20];
+        assert_eq!(editor.paste(0x00), 0);
+        assert_eq!(editor.import_over(0x0C, &[2; 8]), 0);
+        assert!(editor.data.bytes().iter().all(|&byte| byte == 0));
+        assert!(editor.fill_range(0x20, 0x3F, 0xAA));
+        assert_eq!(editor.data[0x20], 0xAA);
+
+        editor.take_snapshot("antes");
+        editor.snapshots.insert("mexe".to_string(), vec![5; 0x40]);
+        assert!(editor.restore_snapshot("mexe").is_err());
+        assert!(editor.restore_snapshot("antes").is_ok());
+        editor.snapshots.insert("curto".to_string(), vec![0; 0x18]);
+        assert!(editor.restore_snapshot("curto").is_err());
+
+        // Registro RLE de 4 bytes em 0x1E e um patch que só trunca depois do bloqueio
+        assert!(editor.apply_ips(b"PATCH\x00\x00\x1E\x00\x00\x00\x04\x07EOF").is_err());
+        assert_eq!(editor.data[0x1E], 0);
+        assert!(editor.apply_ips(b"PATCHEOF\x00\x00\x30").is_ok());
+        assert!(editor.apply_ips(b"PATCHEOF\x00\x00\x18").is_err());
+
+        // Escritas de vários bytes que tocam o bloqueio não escrevem nada
+        assert!(editor.write_pointer(0x0E, 0x00).is_err());
+        assert!(editor.write_int(0x1E, 4, 0x0102_0304, Endian::Little).is_err());
+        assert!(editor.write_string(0x0F, &[1, 2]).is_err());
+        let field = template::FieldView {
+            name: "x".to_string(),
+            kind: template::FieldKind::U16,
+            offset: 0x1F,
+            value: Some("0".to_string()),
+        };
+        assert!(editor.write_field(&field, "258").is_err());
+        for (i, &byte) in lz77::compress(&[3; 0x20]).unwrap().iter().enumerate() {
+            editor.data[i] = byte;
+        }
+        // 12 bytes sem repetição compactam em 20, passando para dentro do bloqueio em 0x10
+        let varied: Vec<u8> = (1..=12).collect();
+        assert!(editor.replace_lz77(0x00, &varied).is_err());
+        assert_eq!((editor.data[0x0E], editor.data[0x0F]), (0, 0));
+        assert!(editor.data.bytes()[0x10..0x20].iter().all(|&byte| byte == 0));
+        assert_eq!(editor.data[0x20], 0xAA);
+
+        // Só as ocorrências fora do bloqueio são substituídas e contadas
+        editor.data[0x04] = 0x42;
+        editor.data[0x18] = 0x42;
+        editor.data[0x28] = 0x42;
+        assert_eq!(editor.replace_all(&[0x42], &[0x43]), 2);
+        assert_eq!((editor.data[0x04], editor.data[0x18], editor.data[0x28]), (0x43, 0x42, 0x43));
+
+        // Cortar o fim não leva um bloqueio junto
+        editor.lock_range(0x2E, 0x2F);
+        assert_eq!(editor.trim_trailing(0xAA), 0);
+        editor.original_len = 0x20;
+        assert_eq!(editor.truncate_to_original(), 0);
+        assert_eq!(editor.data.len(), 0x30);
+        editor.unlock_range(0x2E, 0x2F);
+        assert_eq!(editor.trim_trailing(0xAA), 7);
+
+        // Inserir ou remover dentro do bloqueio é recusado, e o chamador fica sabendo
+        assert!(!editor.insert_byte(0x14, 0));
+        assert!(!editor.delete_byte(0x14));
+        assert!(editor.delete_byte(0x05));
+        assert!(editor.locked_ranges.contains(&(0x0F, 0x1E)));
+    }
+
+    #[test]
+    fn scripts_touching_locks_change_nothing() {
+        let mut editor = editor_with(vec![0u8; 0x40], "dados.bin");
+        editor.lock_range(0x20, 0x23);
+        assert!(editor.run_script("set 0 01 02\nptr 1E 10\n").is_err());
+        assert!(editor.data.bytes().iter().all(|&byte| byte == 0));
+        assert_eq!(editor.run_script("set 0 01 02\nfill 10 1F FF\n").unwrap(), 2);
+        assert_eq!(editor.data[0x1F], 0xFF);
+        editor.read_only = true;
+        assert!(editor.run_script("set 0 05").is_err());
+    }
+
+    #[test]
+    fn streamed_files_refuse_size_changes() {
+        let path = temp_dir("stream").join("grande.bin");
+        fs::write(&path, vec![0u8; 0x300]).unwrap();
+        let mut editor = new_editor();
+        editor.use_stream = true;
+        editor.open_file(&path).unwrap();
+        assert!(!editor.insert_byte(0x10, 1));
+        assert!(!editor.delete_byte(0x10));
+        assert_eq!(editor.pad_to_power_of_two(), 0);
+        assert_eq!(editor.data.len(), 0x300);
+        assert!(editor.data.is_on_disk());
+        assert!(editor.apply_ips(b"PATCH\x00\x02\xFF\x00\x02\x01\x02EOF").is_err());
+        assert_eq!(editor.apply_ips(b"PATCH\x00\x00\x10\x00\x01\x07EOF").unwrap(), 1);
+        assert_eq!(editor.data[0x10], 7);
+        assert!(editor.data.is_on_disk());
+    }
+
+    #[test]
+    fn scrolling_to_the_bottom_keeps_the_last_byte_on_screen() {
+        for len in [0x1000, 0x1001, 0x123F, 0x20] {
+            let mut editor = editor_with(vec![0u8; len], "dados.bin");
+            editor.scroll(isize::MAX);
+            assert_eq!(editor.view_offset, editor.max_view_offset());
+            let (first, last) = editor.visible_range().unwrap();
+            assert_eq!(last, len - 1);
+            // Sem linhas em branco abaixo dos dados quando o arquivo enche a tela
+            if len > editor.visible_rows() * editor.bytes_per_row {
+                assert_eq!((last - first) / editor.bytes_per_row, editor.visible_rows() - 1);
+            }
+            editor.scroll(3);
+            assert_eq!(editor.visible_range().unwrap().1, len - 1);
+        }
+    }
+
+    #[test]
+    fn arrowing_past_the_last_visible_row_scrolls_down() {
+        let mut editor = editor_with(vec![0u8; 0x1000], "dados.bin");
+        let rows = editor.visible_rows();
+        let row = editor.bytes_per_row;
+        editor.cursor_pos = (10, rows - 1);
+        assert_eq!(editor.get_cursor_offset(), Some((rows - 1) * row));
+        editor.move_cursor(0, 1);
+        assert_eq!(editor.cursor_pos.1, rows - 1);
+        assert_eq!(editor.view_offset, row);
+        assert_eq!(editor.get_cursor_offset(), Some(rows * row));
+    }
+
+    #[test]
+    fn arrowing_past_the_first_visible_row_scrolls_up() {
+        let mut editor = editor_with(vec![0u8; 0x1000], "dados.bin");
+        let row = editor.bytes_per_row;
+        editor.view_offset = 4 * row;
+        editor.cursor_pos = (13, 0);
+        editor.move_cursor(0, -1);
+        assert_eq!((editor.cursor_pos.1, editor.view_offset), (0, 3 * row));
+        assert_eq!(editor.get_cursor_offset(), Some(3 * row + 1));
+        // No topo do arquivo o cursor para na primeira linha
+        editor.view_offset = 0;
+        editor.move_cursor(0, -1);
+        assert_eq!((editor.cursor_pos.1, editor.view_offset), (0, 0));
+    }
+
+    #[test]
+    fn arrowing_down_stops_at_the_last_byte() {
+        let mut editor = editor_with(vec![0u8; 0x1000], "dados.bin");
+        editor.goto_offset(0xFFF);
+        let view = editor.view_offset;
+        editor.move_cursor(0, 1);
+        assert_eq!(editor.view_offset, view);
+        assert_eq!(editor.get_cursor_offset(), Some(0xFFF));
+    }
+
+    #[test]
+    fn failed_opens_keep_the_previous_file() {
+        let dir = temp_dir("abrir");
+        let good = dir.join("rom.gba");
+        fs::write(&good, [1, 2, 3, 4]).unwrap();
+        let mut editor = new_editor();
+        editor.open_file(&good).unwrap();
+
+        let missing = dir.join("nao_existe.gba");
+        let error = editor.open_file(&missing).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(open_error_message(&missing, &error).starts_with("Arquivo não encontrado"));
+
+        let error = editor.open_file(&dir).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::IsADirectory);
+        assert!(open_error_message(&dir, &error).starts_with("O caminho é um diretório"));
+
+        // Como root a permissão é ignorada; aí só a mensagem é conferida
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let locked = dir.join("sem_permissao.gba");
+            fs::write(&locked, [9]).unwrap();
+            fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+            let error = match editor.open_file(&locked) {
+                Err(error) => error,
+                Ok(()) => {
+                    editor.close_tab();
+                    io::Error::from(io::ErrorKind::PermissionDenied)
+                }
+            };
+            assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+            assert!(open_error_message(&locked, &error).starts_with("Sem permissão"));
+        }
+
+        assert_eq!(editor.rom_path.as_deref(), Some(good.as_path()));
+        assert_eq!(editor.data.bytes().to_vec(), vec![1, 2, 3, 4]);
+        assert_eq!(editor.tabs.len(), 1);
+    }
+
+    #[test]
+    fn opening_a_smaller_file_clamps_cursor_and_view() {
+        let dir = temp_dir("menor");
+        let (large, small) = (dir.join("grande.bin"), dir.join("pequeno.bin"));
+        fs::write(&large, vec![0u8; 0x10000]).unwrap();
+        fs::write(&small, [1, 2, 3, 4]).unwrap();
+        let mut editor = new_editor();
+        editor.open_file(&large).unwrap();
+        editor.goto_offset(0xFFFF);
+        assert!(editor.view_offset > 0);
+
+        editor.open_file(&small).unwrap();
+        assert_eq!(editor.view_offset, 0);
+        assert!(editor.get_cursor_offset().is_some_and(|offset| offset < 4));
+    }
+
+    #[test]
+    fn footer_prompts_chain_their_answers() {
+        let mut editor = editor_with(vec![0x0F; 0x10], "dados.bin");
+        editor.finish_prompt(PromptAction::Fill(0x00, 0x03), "aa");
+        assert_eq!(editor.data[0x03], 0xAA);
+
+        // A operação abre a pergunta da máscara, que leva o intervalo e a operação escolhidos
+        editor.finish_prompt(PromptAction::Bitwise(0x04, 0x07), "x");
+        let Prompt { action, .. } = editor.prompt.take().unwrap();
+        assert_eq!(action, PromptAction::BitMask(0x04, 0x07, BitOp::Xor));
+        editor.finish_prompt(action, "ff");
+        assert_eq!(editor.data[0x07], 0xF0);
+        assert_eq!(editor.data[0x08], 0x0F);
+
+        // NOT dispensa a máscara; a resposta vazia não faz nada
+        editor.finish_prompt(PromptAction::Bitwise(0x08, 0x08), "n");
+        assert!(editor.prompt.is_none());
+        assert_eq!(editor.data[0x08], 0xF0);
+        editor.finish_prompt(PromptAction::EditByte(0x09), "");
+        assert_eq!(editor.data[0x09], 0x0F);
+
+        // A saída só é confirmada com (S)alvar ou (D)escartar
+        editor.finish_prompt(PromptAction::Quit, "");
+        assert!(!editor.quitting);
+        editor.finish_prompt(PromptAction::Quit, "d");
+        assert!(editor.quitting);
+    }
+
+    #[test]
+    fn imports_past_the_end_wait_for_confirmation() {
+        let file = temp_dir("import").join("extra.bin");
+        fs::write(&file, [1u8; 8]).unwrap();
+        let mut editor = editor_with(vec![0u8; 0x10], "dados.bin");
+        editor.finish_prompt(PromptAction::Import(0x0C), file.to_str().unwrap());
+        let Prompt { action, .. } = editor.prompt.take().unwrap();
+        assert!(matches!(action, PromptAction::ImportConfirm(0x0C, _, _)));
+
+        // Enter cancela; só "s" importa, e só o que cabe
+        editor.finish_prompt(action.clone(), "");
+        assert!(editor.data.bytes().iter().all(|&byte| byte == 0));
+        editor.finish_prompt(action, "s");
+        assert_eq!(editor.data.len(), 0x10);
+        assert_eq!(editor.data[0x0C], 1);
+        assert_eq!(editor.data[0x0F], 1);
+    }
+
+    #[test]
+    fn backup_keeps_the_original_across_saves() {
+        let path = temp_dir("backup").join("rom.gba");
+        fs::write(&path, [1u8; 4]).unwrap();
+        let mut editor = new_editor();
+        editor.open_file(&path).unwrap();
+        editor.edit_byte(0, 2);
+        editor.save_file().unwrap();
+        editor.edit_byte(0, 3);
+        editor.save_file().unwrap();
+        assert_eq!(fs::read(&path).unwrap()[0], 3);
+        assert_eq!(fs::read(sibling_path(&path, ".bak")).unwrap(), [1u8; 4]);
+    }
+
+    #[test]
+    fn hex_input_accepts_an_optional_prefix() {
+        assert_eq!(parse_hex("1F2A"), Some(0x1F2A));
+        assert_eq!(parse_hex("0x1f2a"), Some(0x1F2A));
+        assert_eq!(parse_hex("0X1F2A"), Some(0x1F2A));
+        assert_eq!(parse_hex("0x"), None);
+        assert_eq!(parse_hex("x10"), None);
+        assert_eq!(parse_hex("+1F"), None);
+        assert_eq!(parse_hex("0x+1F"), None);
+        assert_eq!(parse_hex("-1F"), None);
+        assert_eq!(parse_range("0x10-1F"), Some((0x10, 0x1F)));
+    }
+
+    #[test]
+    fn diff_count_matches_in_memory_and_mapped_buffers() {
+        let dir = temp_dir("diferencas");
+        let data: Vec<u8> = (0..0x40).collect();
+        let editors = ["memoria.bin", "mapa.bin"].map(|name| {
+            let path = dir.join(name);
+            fs::write(&path, &data).unwrap();
+            let mut editor = new_editor();
+            editor.backup_on_save = false;
+            editor.load_data(if name == "mapa.bin" { Buffer::map_file(&path).unwrap() } else { Buffer::from(data.clone()) });
+            editor.rom_path = Some(path);
+            editor
+        });
+        for mut editor in editors {
+            editor.edit_byte(0x10, 0xFF);
+            editor.edit_byte(0x11, 0xFF);
+            assert_eq!(editor.diff_count(), 2);
+            // Depois de salvo, só conta o que mudou desde a gravação
+            editor.save_file().unwrap();
+            assert_eq!(editor.diff_count(), 0);
+            editor.edit_byte(0x20, 0x00);
+            assert_eq!(editor.diff_count(), 1);
+            // Inserir em 0x30 desloca os 0x10 bytes seguintes e acrescenta um no fim
+            assert!(editor.insert_byte(0x30, 0xAA));
+            assert_eq!(editor.diff_count(), 2 + 0x10);
+        }
+    }
+
+    #[test]
+    fn invalid_goto_keeps_the_navigation_history() {
+        let mut editor = editor_with(vec![0; 0x400], "rom.gba");
+        editor.finish_prompt(PromptAction::Goto, "zz");
+        editor.finish_prompt(PromptAction::Goto, "abc%");
+        assert!(editor.nav_back.is_empty());
+        editor.finish_prompt(PromptAction::Goto, "0x200");
+        assert_eq!(editor.nav_back.len(), 1);
+        editor.finish_prompt(PromptAction::Goto, "50%");
+        assert_eq!(editor.nav_back.len(), 2);
+    }
+
+    #[test]
+    fn undo_and_redo_track_the_saved_state() {
+        let dir = temp_dir("desfazer");
+        let mut editor = editor_with(vec![0; 8], dir.join("rom.bin").to_str().unwrap());
+        editor.backup_on_save = false;
+        assert_eq!(editor.undo(), None);
+        editor.edit_byte(0, 1);
+        editor.save_file().unwrap();
+        assert!(!editor.modified);
+        editor.edit_byte(1, 2);
+        assert!(editor.modified);
+        // Desfazer até o salvamento deixa o arquivo como gravado; passar dele volta a modificar
+        assert_eq!(editor.undo(), Some(1));
+        assert_eq!((editor.data[1], editor.modified), (0, false));
+        assert_eq!(editor.undo(), Some(0));
+        assert_eq!((editor.data[0], editor.modified), (0, true));
+        assert_eq!(editor.redo(), Some(0));
+        assert_eq!((editor.data[0], editor.modified), (1, false));
+        // Editar depois de desfazer além do salvamento torna o estado salvo inalcançável
+        editor.undo();
+        editor.edit_byte(2, 3);
+        assert_eq!(editor.saved_depth, None);
+        assert_eq!(editor.redo(), None);
+        editor.undo();
+        assert!(editor.modified);
+        assert_eq!(editor.data.bytes().to_vec(), vec![0; 8]);
+    }
+
+    #[test]
+    fn insert_and_delete_shift_edits_and_locks() {
+        let mut editor = editor_with((0..0x20).collect(), "rom.bin");
+        editor.edit_byte(0x02, 0xAA);
+        editor.edit_byte(0x10, 0xBB);
+        editor.lock_range(0x08, 0x0B);
+        assert!(editor.insert_bytes(0x05, &[1, 2, 3]));
+        assert_eq!(editor.data.len(), 0x23);
+        assert_eq!(editor.data.slice(0x04..0x09).to_vec(), vec![0x04, 1, 2, 3, 0x05]);
+        assert_eq!(editor.session_edits, HashSet::from([0x02, 0x05, 0x06, 0x07, 0x13]));
+        assert!(editor.locked_ranges.contains(&(0x0B, 0x0E)));
+        // O histórico guarda offsets antigos, então é descartado
+        assert!(editor.undo_stack.is_empty() && editor.undo().is_none());
+
+        assert!(editor.delete_byte(0x02));
+        assert_eq!(editor.data[0x02], 0x03);
+        assert_eq!(editor.session_edits, HashSet::from([0x04, 0x05, 0x06, 0x12]));
+        assert!(editor.locked_ranges.contains(&(0x0A, 0x0D)));
+        // Dentro de um bloqueio nada muda; logo antes dele, o bloqueio só anda
+        assert!(!editor.insert_byte(0x0B, 0));
+        assert!(editor.insert_byte(0x0A, 0));
+        assert!(editor.locked_ranges.contains(&(0x0B, 0x0E)));
+        assert_eq!(editor.data[0x0B], 0x08);
+    }
+
+    #[test]
+    fn names_come_from_the_detected_game() {
+        let mut data = vec![0; 0x1B2000];
+        data[0x134..0x143].copy_from_slice(b"POKEMON_GLDAAUE");
+        // Espécies em entradas de 10 bytes; golpes um após o outro, cada um até o 0x50
+        data[0x1B0B74 + 10..0x1B0B74 + 13].copy_from_slice(&[0x80, 0x81, 0x50]);
+        data[0x1B1574..0x1B157C].copy_from_slice(&[0x80, 0x50, 0x82, 0x83, 0x84, 0x50, 0x50, 0x50]);
+        let mut editor = editor_with(data, "gold.gbc");
+        editor.table = Some(HashMap::from([(0x80, "A".to_string()), (0x81, "B".to_string()), (0x82, "C".to_string())]));
+        assert_eq!(editor.detect_game(), Some(GameId::Gold));
+        assert_eq!(editor.lookup_species(2).as_deref(), Some("AB"));
+        assert_eq!(editor.lookup_move(1).as_deref(), Some("A"));
+        assert_eq!(editor.lookup_move(2).as_deref(), Some("C[83][84]"));
+        assert_eq!(editor.lookup_move(3).as_deref(), Some(""));
+        assert_eq!(editor.lookup_move(0), None);
+        assert_eq!(editor.lookup_species(252), None);
+        // Sem um jogo reconhecido não há nomes
+        editor.rom_path = Some(PathBuf::from("outro.bin"));
+        editor.edit_byte(0x134, b'X');
+        assert_eq!(editor.lookup_species(2), None);
+    }
+
+    #[test]
+    fn recent_files_live_in_the_config_dir() {
+        let dir = temp_dir("recentes");
+        let (first, second) = (dir.join("a.gba"), dir.join("b.gba"));
+        fs::write(&first, [1]).unwrap();
+        fs::write(&second, [2]).unwrap();
+        let mut editor = HexEditor::new();
+        editor.config_dir = Some(dir.join("config"));
+        fs::create_dir_all(dir.join("config")).unwrap();
+        let _ = fs::remove_file(dir.join("config").join(".pokehex_recent"));
+        for path in [&first, &second, &first] {
+            editor.open_file(path).unwrap();
+        }
+        let canonical = |path: &Path| fs::canonicalize(path).unwrap();
+        assert_eq!(editor.load_recent_files(), vec![canonical(&first), canonical(&second)]);
+        // Sem pasta de configuração não há lista, e abrir continua funcionando
+        editor.config_dir = None;
+        editor.open_file(&second).unwrap();
+        assert!(editor.load_recent_files().is_empty());
+    }
+}
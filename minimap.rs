@@ -0,0 +1,93 @@
+// Visão geral do arquivo: cada linha de pixels é um trecho colorido pela entropia dos seus bytes,
+// para destacar preenchimento (baixa), texto e código (média) e dados compactados (alta)
+use std::borrow::Cow;
+use std::ops::Range;
+
+// Cor de cada uma das `rows` linhas, dividindo os `len` bytes em trechos iguais.
+// `chunks` devolve os bytes de uma faixa em pedaços, para não ler um arquivo grande inteiro de uma vez.
+pub fn colors<'a, I>(len: usize, rows: usize, chunks: impl Fn(Range<usize>) -> I) -> Vec<u32>
+where
+    I: Iterator<Item = Cow<'a, [u8]>>,
+{
+    if len == 0 || rows == 0 {
+        return vec![0; rows];
+    }
+    let chunk = chunk_len(len, rows);
+    (0..rows)
+        .map(|row| {
+            let start = (row * chunk).min(len);
+            let end = (start + chunk).min(len);
+            if start == end {
+                return 0;
+            }
+            let mut counts = [0usize; 256];
+            for piece in chunks(start..end) {
+                for &byte in piece.iter() {
+                    counts[byte as usize] += 1;
+                }
+            }
+            color(entropy(&counts, end - start))
+        })
+        .collect()
+}
+
+// Bytes representados por linha (ao menos 1)
+pub fn chunk_len(len: usize, rows: usize) -> usize {
+    len.div_ceil(rows.max(1)).max(1)
+}
+
+// Entropia de Shannon em bits por byte (0 a 8), a partir de quantas vezes cada valor aparece
+fn entropy(counts: &[usize; 256], total: usize) -> f32 {
+    let total = total as f32;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f32 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// Azul escuro para entropia baixa, verde para média e vermelho para alta
+fn color(entropy: f32) -> u32 {
+    let t = (entropy / 8.0).clamp(0.0, 1.0);
+    let (r, g, b) = if t < 0.5 {
+        let k = t * 2.0;
+        (0.0, k, 0.4 * (1.0 - k) + 0.1)
+    } else {
+        let k = (t - 0.5) * 2.0;
+        (k, 1.0 - k, 0.0)
+    };
+    let channel = |value: f32| (value * 255.0).round() as u32;
+    (channel(r) << 16) | (channel(g) << 8) | channel(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colors_of(bytes: &[u8], rows: usize, piece: usize) -> Vec<u32> {
+        colors(bytes.len(), rows, |range: Range<usize>| bytes[range].chunks(piece).map(Cow::Borrowed).collect::<Vec<_>>().into_iter())
+    }
+
+    #[test]
+    fn rows_follow_the_entropy() {
+        let mut bytes = vec![0u8; 256];
+        bytes.extend(0..=255u8);
+        let colors = colors_of(&bytes, 2, 256);
+        // Preenchimento é azul escuro; todos os valores igualmente é vermelho puro
+        assert_eq!(colors, vec![color(0.0), color(8.0)]);
+        assert_eq!((colors[0], colors[1]), (0x000080, 0xFF0000));
+        // O tamanho dos pedaços lidos não muda o resultado
+        assert_eq!(colors_of(&bytes, 2, 7), colors);
+    }
+
+    #[test]
+    fn short_files_leave_the_last_rows_empty() {
+        assert_eq!(chunk_len(10, 4), 3);
+        assert_eq!(chunk_len(0, 4), 1);
+        assert_eq!(colors_of(&[7; 3], 4, 64), vec![color(0.0), color(0.0), color(0.0), 0]);
+        assert_eq!(colors_of(&[], 2, 64), vec![0, 0]);
+    }
+}
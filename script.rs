@@ -0,0 +1,109 @@
+// Roteiros de edição em texto puro, para documentar e reaplicar um hack. Um comando por linha:
+//   set <offset> <bytes...>     gravar os bytes a partir do offset
+//   fill <início> <fim> <byte>  preencher o intervalo (inclusivo)
+//   ptr <offset> <destino>      gravar o ponteiro GBA para o destino
+// Números em hexadecimal, com ou sem 0x; linhas vazias e comentários (#) são ignorados.
+
+pub enum Command {
+    Set { offset: usize, bytes: Vec<u8> },
+    Fill { start: usize, end: usize, value: u8 },
+    Pointer { at: usize, target: usize },
+}
+
+pub struct Script {
+    pub commands: Vec<(usize, Command)>,  // (número da linha, comando)
+    pub errors: Vec<(usize, String)>,     // (número da linha, erro) das linhas inválidas
+}
+
+impl Command {
+    // Intervalo (inclusivo) de bytes escritos pelo comando; None se o fim não couber em um usize
+    pub fn span(&self) -> Option<(usize, usize)> {
+        match *self {
+            Command::Set { offset, ref bytes } => Some((offset, offset.checked_add(bytes.len() - 1)?)),
+            Command::Fill { start, end, .. } => Some((start.min(end), start.max(end))),
+            Command::Pointer { at, .. } => Some((at, at.checked_add(3)?)),
+        }
+    }
+}
+
+fn number(token: &str) -> Option<usize> {
+    crate::parse_offset(token)
+}
+
+fn byte(token: &str) -> Option<u8> {
+    number(token).and_then(|value| u8::try_from(value).ok())
+}
+
+fn parse_line(line: &str) -> Result<Command, String> {
+    let mut tokens = line.split_whitespace();
+    let name = tokens.next().unwrap_or_default();
+    let args: Vec<&str> = tokens.collect();
+    let usage = |form: &str| Err(format!("uso: {}", form));
+    match name.to_lowercase().as_str() {
+        "set" => {
+            let Some((offset, bytes)) = args.split_first() else {
+                return usage("set <offset> <bytes...>");
+            };
+            match (number(offset), bytes.iter().map(|token| byte(token)).collect::<Option<Vec<u8>>>()) {
+                (Some(offset), Some(bytes)) if !bytes.is_empty() => Ok(Command::Set { offset, bytes }),
+                _ => usage("set <offset> <bytes...>"),
+            }
+        }
+        "fill" => match args[..] {
+            [start, end, value] => match (number(start), number(end), byte(value)) {
+                (Some(start), Some(end), Some(value)) => Ok(Command::Fill { start, end, value }),
+                _ => usage("fill <início> <fim> <byte>"),
+            },
+            _ => usage("fill <início> <fim> <byte>"),
+        },
+        "ptr" => match args[..] {
+            [at, target] => match (number(at), number(target)) {
+                (Some(at), Some(target)) => Ok(Command::Pointer { at, target }),
+                _ => usage("ptr <offset> <destino>"),
+            },
+            _ => usage("ptr <offset> <destino>"),
+        },
+        other => Err(format!("comando desconhecido: {}", other)),
+    }
+}
+
+pub fn parse(text: &str) -> Script {
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match parse_line(line) {
+            Ok(command) if command.span().is_none() => errors.push((i + 1, "offset grande demais".to_string())),
+            Ok(command) => commands.push((i + 1, command)),
+            Err(message) => errors.push((i + 1, message)),
+        }
+    }
+    Script { commands, errors }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_and_collects_errors() {
+        let script = parse("# hack\nset 0x100 01 ff\n\nfill 10 1F 00\nptr 200 0x1000\nmove 1 2\nset 100\nfill 1 2 100\n");
+        assert_eq!(script.commands.len(), 3);
+        assert!(matches!(&script.commands[0], (2, Command::Set { offset: 0x100, bytes }) if bytes == &[0x01, 0xFF]));
+        assert!(matches!(script.commands[1], (4, Command::Fill { start: 0x10, end: 0x1F, value: 0 })));
+        assert!(matches!(script.commands[2], (5, Command::Pointer { at: 0x200, target: 0x1000 })));
+        let lines: Vec<usize> = script.errors.iter().map(|&(line, _)| line).collect();
+        assert_eq!(lines, [6, 7, 8]);
+    }
+
+    #[test]
+    fn spans_that_overflow_are_errors() {
+        let script = parse("set FFFFFFFFFFFFFFFF 01 02\nptr FFFFFFFFFFFFFFFE 0\nset FFFFFFFFFFFFFFFF 01\n");
+        assert_eq!(script.errors.iter().map(|&(line, _)| line).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(script.commands[0].1.span(), Some((usize::MAX, usize::MAX)));
+        assert_eq!(parse("fill 20 10 FF").commands[0].1.span(), Some((0x10, 0x20)));
+    }
+}
@@ -0,0 +1,221 @@
+// Tabelas de caracteres (.tbl) usadas pelos jogos de Pokémon no lugar do ASCII
+use std::collections::HashMap;
+
+// Interpretar linhas no formato "A1=A"; linhas vazias, comentários (#) e chaves de mais de um byte são ignorados
+pub fn parse(text: &str) -> HashMap<u8, String> {
+    let mut table = HashMap::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.len() != 2 {
+            continue;
+        }
+        if let Ok(byte) = u8::from_str_radix(key, 16) {
+            table.insert(byte, value.to_string());
+        }
+    }
+    table
+}
+
+// Convenções de texto do jogo: bytes que encerram um texto e códigos de controle (quebra de linha,
+// espera por um botão...), que aparecem como {NOME} no texto decodificado
+pub struct TextCodes {
+    pub terminators: Vec<u8>,  // O primeiro é o gravado no fim dos textos editados
+    pub controls: HashMap<u8, String>,
+}
+
+impl Default for TextCodes {
+    // 3ª geração: 0xFF encerra e 0xFE quebra a linha
+    fn default() -> Self {
+        TextCodes { terminators: vec![0xFF], controls: HashMap::from([(0xFE, "BR".to_string())]) }
+    }
+}
+
+impl TextCodes {
+    pub fn is_terminator(&self, byte: u8) -> bool {
+        self.terminators.contains(&byte)
+    }
+
+    // Terminadores para as mensagens, ex: "0x50/0xFF"
+    pub fn terminators_label(&self) -> String {
+        let bytes: Vec<String> = self.terminators.iter().map(|byte| format!("0x{:02X}", byte)).collect();
+        bytes.join("/")
+    }
+}
+
+// Interpretar linhas "terminadores=50 FF" e "controle=4F BR"; a primeira linha de terminadores
+// substitui os padrões e a primeira de controle descarta os códigos padrão. Linhas vazias e
+// comentários (#) são ignorados; as inválidas viram avisos.
+pub fn parse_codes(text: &str) -> (TextCodes, Vec<String>) {
+    let mut codes = TextCodes::default();
+    let mut warnings = Vec::new();
+    let mut controls_set = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            warnings.push(format!("linha sem '=': {}", line));
+            continue;
+        };
+        match key.trim() {
+            "terminadores" => {
+                let bytes: Option<Vec<u8>> = value.split_whitespace().map(|byte| u8::from_str_radix(byte, 16).ok()).collect();
+                match bytes {
+                    Some(bytes) if !bytes.is_empty() => codes.terminators = bytes,
+                    _ => warnings.push(format!("terminadores inválidos: {}", value.trim())),
+                }
+            }
+            "controle" => {
+                let mut parts = value.split_whitespace();
+                match (parts.next().and_then(|byte| u8::from_str_radix(byte, 16).ok()), parts.next(), parts.next()) {
+                    (Some(byte), Some(name), None) => {
+                        if !controls_set {
+                            codes.controls.clear();
+                            controls_set = true;
+                        }
+                        codes.controls.insert(byte, name.to_string());
+                    }
+                    _ => warnings.push(format!("controle inválido (use \"controle=FE BR\"): {}", value.trim())),
+                }
+            }
+            other => warnings.push(format!("chave desconhecida: {}", other)),
+        }
+    }
+    (codes, warnings)
+}
+
+// Texto dos bytes pela tabela; códigos de controle aparecem como {NOME} e bytes sem entrada como [XX]
+pub fn decode(table: &HashMap<u8, String>, codes: &TextCodes, bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| match (codes.controls.get(byte), table.get(byte)) {
+            (Some(name), _) => format!("{{{}}}", name),
+            (None, Some(text)) => text.clone(),
+            (None, None) => format!("[{:02X}]", byte),
+        })
+        .collect()
+}
+
+// Bytes do texto pela tabela inversa, sempre com a entrada mais longa que casar (ex.: "[PLAYER]"
+// antes de "[" e de um [XX]); {NOME} escreve o código de controle e [XX] o byte direto. None se algum trecho não tiver entrada.
+pub fn encode(table: &HashMap<u8, String>, codes: &TextCodes, text: &str) -> Option<Vec<u8>> {
+    // Para cada texto, o menor byte que o produz, para a escolha não depender da ordem do HashMap
+    let controls: Vec<(String, u8)> = codes.controls.iter().map(|(&byte, name)| (format!("{{{}}}", name), byte)).collect();
+    let mut reverse: HashMap<&str, u8> = HashMap::new();
+    let entries = table.iter().map(|(&byte, value)| (value.as_str(), byte));
+    for (value, byte) in entries.chain(controls.iter().map(|(name, byte)| (name.as_str(), *byte))) {
+        if !value.is_empty() {
+            let entry = reverse.entry(value).or_insert(byte);
+            *entry = (*entry).min(byte);
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        // [XX] conta como uma entrada de 4 caracteres, para "[" sozinho na tabela não esconder o escape.
+        // from_str_radix aceitaria um sinal, como em "[+1]", então os dígitos são conferidos antes.
+        let escape = rest
+            .get(..4)
+            .filter(|s| s.starts_with('[') && s.ends_with(']'))
+            .and_then(|s| s.get(1..3))
+            .filter(|digits| digits.bytes().all(|c| c.is_ascii_hexdigit()))
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+        let matched = reverse.iter().filter(|(value, _)| rest.starts_with(**value)).max_by_key(|(value, _)| value.len());
+        match (matched, escape) {
+            (_, Some(byte)) if matched.is_none_or(|(value, _)| value.len() < 4) => {
+                out.push(byte);
+                rest = &rest[4..];
+            }
+            (Some((value, &byte)), _) => {
+                out.push(byte);
+                rest = &rest[value.len()..];
+            }
+            (None, _) => return None,
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> HashMap<u8, String> {
+        HashMap::from([
+            (0xA1, "0".to_string()),
+            (0xBB, "A".to_string()),
+            (0xD5, "[".to_string()),
+            (0xF0, "[PLAYER]".to_string()),
+            (0xF1, "A".to_string()),
+            (0x1B, "é".to_string()),
+        ])
+    }
+
+    #[test]
+    fn encode_prefers_the_longest_entry() {
+        let codes = TextCodes::default();
+        assert_eq!(encode(&table(), &codes, "[PLAYER]A["), Some(vec![0xF0, 0xBB, 0xD5]));
+        // Dois bytes com o mesmo texto: fica o menor
+        assert_eq!(encode(&table(), &codes, "AA"), Some(vec![0xBB, 0xBB]));
+        assert_eq!(encode(&table(), &codes, "é0"), Some(vec![0x1B, 0xA1]));
+        // O escape de 4 caracteres vence o "[" sozinho, mas não "[PLAYER]"
+        assert_eq!(encode(&table(), &codes, "[0A][[PLAYER]"), Some(vec![0x0A, 0xD5, 0xF0]));
+        assert_eq!(encode(&table(), &codes, "Z"), None);
+    }
+
+    #[test]
+    fn controls_and_escapes_round_trip() {
+        let codes = TextCodes::default();
+        let bytes = [0xBB, 0xFE, 0x07, 0xF0, 0xFF];
+        let text = decode(&table(), &codes, &bytes);
+        assert_eq!(text, "A{BR}[07][PLAYER][FF]");
+        assert_eq!(encode(&table(), &codes, &text), Some(bytes.to_vec()));
+        assert_eq!(encode(&table(), &codes, "{BR}[fe]"), Some(vec![0xFE, 0xFE]));
+    }
+
+    #[test]
+    fn bad_escapes_are_refused() {
+        let codes = TextCodes::default();
+        for text in ["[+1]", "[G0]", "[0", "[é]", "[aé]", "{XX}"] {
+            assert_eq!(encode(&HashMap::new(), &codes, text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn codes_replace_the_defaults() {
+        let (codes, warnings) = parse_codes("# Gen 1\nterminadores=50 ff\ncontrole=4F BR\ncontrole=51 PARA\n");
+        assert!(warnings.is_empty());
+        assert_eq!(codes.terminators, vec![0x50, 0xFF]);
+        assert_eq!(codes.terminators_label(), "0x50/0xFF");
+        assert_eq!(codes.controls, HashMap::from([(0x4F, "BR".to_string()), (0x51, "PARA".to_string())]));
+
+        let (codes, warnings) = parse_codes("terminadores=\ncontrole=FE\nsem igual\nfim=50");
+        assert_eq!(warnings.len(), 4);
+        assert_eq!(codes.terminators, vec![0xFF]);
+        assert_eq!(codes.controls.get(&0xFE).map(String::as_str), Some("BR"));
+    }
+
+    #[test]
+    fn tbl_files_round_trip() {
+        let text = "# Tabela\r\n00= \r\nBB=A\r\nbc=B\r\n35==\r\n\r\n1234=XY\r\nZZ=?\r\nsem igual\r\n";
+        let table = parse(text);
+        assert_eq!(
+            table,
+            HashMap::from([(0x00, " ".to_string()), (0xBB, "A".to_string()), (0xBC, "B".to_string()), (0x35, "=".to_string())])
+        );
+        let codes = TextCodes::default();
+        let bytes = [0xBB, 0x00, 0xBC, 0x35, 0x99, 0xFE, 0xFF];
+        let decoded = decode(&table, &codes, &bytes);
+        assert_eq!(decoded, "A B=[99]{BR}[FF]");
+        assert_eq!(encode(&table, &codes, &decoded), Some(bytes.to_vec()));
+    }
+}
@@ -0,0 +1,281 @@
+// Modelos de estrutura: campos com nome e tipo sobrepostos aos bytes, para ler e editar registros
+// conhecidos (ex.: os atributos base de uma espécie). Formato em texto, uma chave por linha:
+//   nome=Atributos base
+//   offset=0x1FEC18              opcional; sem ele, o modelo é aplicado no cursor
+//   campo=hp u8                  campos em sequência: u8, i8, u16, i16, u32, i32, ptr ou "bytes N"
+// Linhas vazias e comentários (#) são ignorados.
+use crate::inspect::Endian;
+use crate::GBA_ROM_BASE;
+use std::io;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FieldKind {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    Pointer,       // Ponteiro GBA de 32 bits, sempre little-endian
+    Bytes(usize),  // Bytes crus, exibidos em hexadecimal
+}
+
+impl FieldKind {
+    pub fn size(self) -> usize {
+        match self {
+            FieldKind::U8 | FieldKind::I8 => 1,
+            FieldKind::U16 | FieldKind::I16 => 2,
+            FieldKind::U32 | FieldKind::I32 | FieldKind::Pointer => 4,
+            FieldKind::Bytes(len) => len,
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            FieldKind::U8 => "u8".to_string(),
+            FieldKind::I8 => "i8".to_string(),
+            FieldKind::U16 => "u16".to_string(),
+            FieldKind::I16 => "i16".to_string(),
+            FieldKind::U32 => "u32".to_string(),
+            FieldKind::I32 => "i32".to_string(),
+            FieldKind::Pointer => "ptr".to_string(),
+            FieldKind::Bytes(len) => format!("bytes {}", len),
+        }
+    }
+
+    fn signed(self) -> bool {
+        matches!(self, FieldKind::I8 | FieldKind::I16 | FieldKind::I32)
+    }
+
+    // Valor dos bytes do campo para exibição
+    pub fn decode(self, bytes: &[u8], endian: Endian) -> String {
+        if let FieldKind::Bytes(_) = self {
+            let hex: Vec<String> = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+            return hex.join(" ");
+        }
+        let endian = if self == FieldKind::Pointer { Endian::Little } else { endian };
+        let mut value: u64 = 0;
+        for i in 0..bytes.len() {
+            let byte = match endian {
+                Endian::Little => bytes[bytes.len() - 1 - i],
+                Endian::Big => bytes[i],
+            };
+            value = (value << 8) | byte as u64;
+        }
+        match self {
+            FieldKind::Pointer => format!("0x{:08X}", value),
+            FieldKind::I8 => (value as u8 as i8).to_string(),
+            FieldKind::I16 => (value as u16 as i16).to_string(),
+            FieldKind::I32 => (value as u32 as i32).to_string(),
+            _ => value.to_string(),
+        }
+    }
+
+    // Bytes do campo a partir do texto digitado: decimal nos inteiros, hex nos bytes e no ponteiro
+    // (um offset do arquivo vira o endereço 0x08000000 + offset). None se não couber no tipo.
+    pub fn encode(self, input: &str, endian: Endian) -> Option<Vec<u8>> {
+        let input = input.trim();
+        let value: u64 = match self {
+            FieldKind::Bytes(len) => {
+                let bytes: Vec<u8> = input.split_whitespace().map(|byte| u8::from_str_radix(byte, 16).ok()).collect::<Option<_>>()?;
+                return (bytes.len() == len).then_some(bytes);
+            }
+            FieldKind::Pointer => {
+                let address = u32::try_from(crate::parse_hex(input)?).ok()?;
+                let base = GBA_ROM_BASE as u32;
+                (if address < base { address.checked_add(base)? } else { address }) as u64
+            }
+            _ if self.signed() => {
+                let value: i64 = input.parse().ok()?;
+                let bits = self.size() as u32 * 8;
+                if value < -(1i64 << (bits - 1)) || value >= 1i64 << (bits - 1) {
+                    return None;
+                }
+                (value as u64) & ((1u64 << bits) - 1)
+            }
+            _ => {
+                let value: u64 = input.parse().ok()?;
+                if value >> (self.size() * 8) != 0 {
+                    return None;
+                }
+                value
+            }
+        };
+        let mut bytes: Vec<u8> = (0..self.size()).map(|i| (value >> (8 * i)) as u8).collect();
+        if endian == Endian::Big && self != FieldKind::Pointer {
+            bytes.reverse();
+        }
+        Some(bytes)
+    }
+}
+
+pub struct Field {
+    pub name: String,
+    pub kind: FieldKind,
+    pub offset: usize,  // Relativo ao início da estrutura
+}
+
+pub struct Template {
+    pub name: String,
+    pub offset: Option<usize>,
+    pub fields: Vec<Field>,
+}
+
+impl Template {
+    // Bytes ocupados pela estrutura inteira; `parse` recusa os modelos em que a soma não cabe
+    pub fn size(&self) -> usize {
+        self.fields.last().map_or(0, |field| field.offset.saturating_add(field.kind.size()))
+    }
+}
+
+// Um campo aplicado aos dados: offset absoluto e valor (None se passar do fim do arquivo)
+pub struct FieldView {
+    pub name: String,
+    pub kind: FieldKind,
+    pub offset: usize,
+    pub value: Option<String>,
+}
+
+impl FieldView {
+    // Se o offset cai nos bytes do campo, sem somar o fim (que pode passar de usize::MAX)
+    pub fn contains(&self, offset: usize) -> bool {
+        offset >= self.offset && offset - self.offset < self.kind.size()
+    }
+}
+
+fn invalid(line: usize, message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("modelo inválido: linha {}: {}", line, message))
+}
+
+fn parse_kind(words: &[&str]) -> Option<FieldKind> {
+    match *words {
+        ["u8"] => Some(FieldKind::U8),
+        ["i8"] => Some(FieldKind::I8),
+        ["u16"] => Some(FieldKind::U16),
+        ["i16"] => Some(FieldKind::I16),
+        ["u32"] => Some(FieldKind::U32),
+        ["i32"] => Some(FieldKind::I32),
+        ["ptr"] => Some(FieldKind::Pointer),
+        ["bytes", len] => len.parse().ok().filter(|&len| len > 0).map(FieldKind::Bytes),
+        _ => None,
+    }
+}
+
+pub fn parse(text: &str) -> io::Result<Template> {
+    let mut template = Template { name: "modelo".to_string(), offset: None, fields: Vec::new() };
+    let mut next = 0;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(invalid(i + 1, format!("linha sem '=': {}", line)));
+        };
+        let value = value.trim();
+        match key.trim() {
+            "nome" => template.name = value.to_string(),
+            "offset" => {
+                let offset = crate::parse_offset(value).ok_or_else(|| invalid(i + 1, format!("offset inválido: {}", value)))?;
+                template.offset = Some(offset);
+            }
+            "campo" => {
+                let words: Vec<&str> = value.split_whitespace().collect();
+                let (name, kind) = match words.split_first() {
+                    Some((name, kind)) => (name, parse_kind(kind)),
+                    None => (&"", None),
+                };
+                let kind = kind.ok_or_else(|| invalid(i + 1, format!("campo inválido (ex: \"campo=hp u8\"): {}", value)))?;
+                template.fields.push(Field { name: name.to_string(), kind, offset: next });
+                next = next.checked_add(kind.size()).ok_or_else(|| invalid(i + 1, format!("campo grande demais: {}", value)))?;
+            }
+            other => return Err(invalid(i + 1, format!("chave desconhecida: {}", other))),
+        }
+    }
+    if template.fields.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "modelo inválido: nenhum campo"));
+    }
+    if template.offset.is_some_and(|offset| offset.checked_add(next).is_none()) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "modelo inválido: a estrutura passa do fim a partir do offset"));
+    }
+    Ok(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_follow_each_other() {
+        let template = parse("nome=Atributos\n# comentário\n\noffset=0x100\ncampo=hp u8\ncampo=id u16\ncampo=nome bytes 10\ncampo=ptr ptr").unwrap();
+        assert_eq!((template.name.as_str(), template.offset), ("Atributos", Some(0x100)));
+        let fields: Vec<(&str, FieldKind, usize)> = template.fields.iter().map(|f| (f.name.as_str(), f.kind, f.offset)).collect();
+        assert_eq!(
+            fields,
+            vec![("hp", FieldKind::U8, 0), ("id", FieldKind::U16, 1), ("nome", FieldKind::Bytes(10), 3), ("ptr", FieldKind::Pointer, 13)]
+        );
+        assert_eq!(template.size(), 17);
+    }
+
+    #[test]
+    fn invalid_templates_are_refused() {
+        for text in [
+            "",
+            "nome=vazio",
+            "campo hp u8",
+            "tamanho=4\ncampo=hp u8",
+            "campo=hp u64",
+            "campo=hp bytes 0",
+            "campo=hp",
+            "offset=zz\ncampo=hp u8",
+            "campo=x bytes 18446744073709551615\ncampo=y u8",
+            "offset=0x10\ncampo=x bytes 18446744073709551615",
+        ] {
+            assert!(parse(text).is_err(), "{:?}", text);
+        }
+        // Um campo do tamanho do espaço inteiro ainda cabe a partir do zero
+        let template = parse("campo=x bytes 18446744073709551615").unwrap();
+        assert_eq!(template.size(), usize::MAX);
+        let view = FieldView { name: "x".to_string(), kind: template.fields[0].kind, offset: 1, value: None };
+        assert!(view.contains(usize::MAX) && !view.contains(0));
+    }
+
+    #[test]
+    fn encode_checks_sign_and_range() {
+        let le = Endian::Little;
+        assert_eq!(FieldKind::U8.encode("255", le), Some(vec![0xFF]));
+        assert_eq!(FieldKind::U8.encode("256", le), None);
+        assert_eq!(FieldKind::U8.encode("-1", le), None);
+        assert_eq!(FieldKind::I8.encode("-128", le), Some(vec![0x80]));
+        assert_eq!(FieldKind::I8.encode("127", le), Some(vec![0x7F]));
+        assert_eq!(FieldKind::I8.encode("128", le), None);
+        assert_eq!(FieldKind::I8.encode("-129", le), None);
+        assert_eq!(FieldKind::I16.encode("-2", le), Some(vec![0xFE, 0xFF]));
+        assert_eq!(FieldKind::U16.encode("4660", Endian::Big), Some(vec![0x12, 0x34]));
+        assert_eq!(FieldKind::U32.encode("4294967296", le), None);
+        assert_eq!(FieldKind::I32.encode("-2147483648", le), Some(vec![0x00, 0x00, 0x00, 0x80]));
+        assert_eq!(FieldKind::U16.encode("abc", le), None);
+        assert_eq!(FieldKind::Bytes(2).encode("01 ab", le), Some(vec![0x01, 0xAB]));
+        assert_eq!(FieldKind::Bytes(2).encode("01", le), None);
+    }
+
+    #[test]
+    fn pointers_become_rom_addresses() {
+        // Um offset do arquivo vira endereço; o ponteiro é little-endian mesmo em big-endian
+        assert_eq!(FieldKind::Pointer.encode("1F2A", Endian::Big), Some(vec![0x2A, 0x1F, 0x00, 0x08]));
+        assert_eq!(FieldKind::Pointer.encode("0x08001000", Endian::Little), Some(vec![0x00, 0x10, 0x00, 0x08]));
+        assert_eq!(FieldKind::Pointer.encode("+1F", Endian::Little), None);
+        assert_eq!(FieldKind::Pointer.encode("100000000", Endian::Little), None);
+        assert_eq!(FieldKind::Pointer.encode("F8000000", Endian::Little), Some(vec![0x00, 0x00, 0x00, 0xF8]));
+    }
+
+    #[test]
+    fn decode_follows_kind_and_endianness() {
+        assert_eq!(FieldKind::I8.decode(&[0xFF], Endian::Little), "-1");
+        assert_eq!(FieldKind::U16.decode(&[0x34, 0x12], Endian::Little), "4660");
+        assert_eq!(FieldKind::U16.decode(&[0x34, 0x12], Endian::Big), "13330");
+        assert_eq!(FieldKind::I32.decode(&[0x00, 0x00, 0x00, 0x80], Endian::Little), "-2147483648");
+        assert_eq!(FieldKind::Pointer.decode(&[0x34, 0x12, 0x00, 0x08], Endian::Big), "0x08001234");
+        assert_eq!(FieldKind::Bytes(2).decode(&[0x01, 0xAB], Endian::Little), "01 AB");
+    }
+}
@@ -0,0 +1,127 @@
+// Cores da grade como códigos SGR do ANSI (ex.: "7" inverte, "44" fundo azul); vazio usa a cor padrão do terminal
+use std::io;
+
+#[derive(Clone)]
+pub struct Theme {
+    pub gutter: String,        // Coluna de endereços
+    pub hex: String,           // Bytes no painel hexadecimal
+    pub ascii: String,         // Caracteres no painel de texto
+    pub cursor: String,        // Cursor no painel ativo
+    pub cursor_other: String,  // Posição do cursor no outro painel
+    pub selection: String,
+    pub modified: String,      // Bytes editados nesta sessão
+    pub bookmark: String,
+    pub diff: String,          // Diferenças em relação à referência
+    pub pending: String,       // Nibble alto já digitado
+    pub record_alt: String,    // Registros ímpares de uma tabela, alternando com a cor dos bytes
+    pub locked: String,        // Bytes em intervalos bloqueados contra edição
+}
+
+impl Theme {
+    // Temas embutidos: "escuro" (padrão), "claro" e "alto-contraste"
+    pub fn preset(name: &str) -> Option<Theme> {
+        let codes = match name {
+            "escuro" | "dark" => ["", "", "", "7", "4", "44", "32", "4;35", "31", "30;43", "36", "90"],
+            "claro" | "light" => ["34", "", "", "7", "4", "30;106", "32", "4;35", "31", "30;103", "36", "90"],
+            "alto-contraste" | "high-contrast" => [
+                "1;97", "97", "97", "30;107", "4;1;97", "1;97;44", "1;92", "4;1;95", "1;91", "30;103", "1;96", "1;93",
+            ],
+            _ => return None,
+        };
+        let [gutter, hex, ascii, cursor, cursor_other, selection, modified, bookmark, diff, pending, record_alt, locked] =
+            codes.map(String::from);
+        Some(Theme { gutter, hex, ascii, cursor, cursor_other, selection, modified, bookmark, diff, pending, record_alt, locked })
+    }
+
+    // Tudo esmaecido, para a grade ficar de fundo atrás da ajuda
+    pub fn dimmed() -> Theme {
+        let [gutter, hex, ascii, cursor, cursor_other, selection, modified, bookmark, diff, pending, record_alt, locked] =
+            ["2", "2", "2", "2;7", "2", "2", "2", "2", "2", "2", "2", "2"].map(String::from);
+        Theme { gutter, hex, ascii, cursor, cursor_other, selection, modified, bookmark, diff, pending, record_alt, locked }
+    }
+
+    // Texto com a cor do código, restaurando a cor padrão depois
+    pub fn paint(code: &str, text: &str) -> String {
+        if code.is_empty() {
+            text.to_string()
+        } else {
+            format!("\x1B[{}m{}\x1B[0m", code, text)
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::preset("escuro").unwrap()
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("tema inválido: {}", message))
+}
+
+// Interpretar linhas "chave=código"; "tema=nome" parte de um tema embutido e as demais chaves o ajustam.
+// Linhas vazias e comentários (#) são ignorados.
+pub fn parse(text: &str) -> io::Result<Theme> {
+    let mut theme = Theme::default();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(invalid(format!("linha sem '=': {}", line)));
+        };
+        let value = value.trim().to_string();
+        let field = match key.trim() {
+            "tema" => {
+                theme = Theme::preset(&value).ok_or_else(|| invalid(format!("tema desconhecido: {}", value)))?;
+                continue;
+            }
+            "endereco" => &mut theme.gutter,
+            "hex" => &mut theme.hex,
+            "ascii" => &mut theme.ascii,
+            "cursor" => &mut theme.cursor,
+            "cursor_outro" => &mut theme.cursor_other,
+            "selecao" => &mut theme.selection,
+            "modificado" => &mut theme.modified,
+            "marcador" => &mut theme.bookmark,
+            "diferenca" => &mut theme.diff,
+            "nibble" => &mut theme.pending,
+            "registro_alt" => &mut theme.record_alt,
+            "bloqueado" => &mut theme.locked,
+            other => return Err(invalid(format!("chave desconhecida: {}", other))),
+        };
+        *field = value;
+    }
+    Ok(theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_adjust_the_chosen_preset() {
+        let theme = parse("# meu tema\n\ntema=claro\ncursor = 1;7\nmodificado=\n").unwrap();
+        assert_eq!((theme.gutter.as_str(), theme.cursor.as_str(), theme.modified.as_str()), ("34", "1;7", ""));
+        assert_eq!(theme.selection, Theme::preset("light").unwrap().selection);
+        // As chaves antes de "tema" são descartadas pelo tema escolhido
+        let theme = parse("hex=31\ntema=escuro").unwrap();
+        assert_eq!(theme.hex, "");
+    }
+
+    #[test]
+    fn bad_lines_are_refused() {
+        for text in ["cursor", "tema=roxo", "fundo=44"] {
+            assert!(parse(text).is_err(), "{:?}", text);
+        }
+        assert!(Theme::preset("alto-contraste").is_some() && Theme::preset("").is_none());
+    }
+
+    #[test]
+    fn paint_wraps_only_non_empty_codes() {
+        assert_eq!(Theme::paint("", "AB"), "AB");
+        assert_eq!(Theme::paint("4;35", "AB"), "\x1B[4;35mAB\x1B[0m");
+    }
+}
@@ -0,0 +1,173 @@
+// Gráficos do GBA: tiles 8x8 de 4 bits por pixel (32 bytes cada) e cores BGR555
+
+pub const TILE_BYTES: usize = 32;
+const BACKGROUND: u32 = 0x202020;
+pub const CURSOR: u32 = 0xFF00FF;  // Contorno do cursor, numa cor que quase nunca aparece nos gráficos
+
+// Sem paleta carregada, os 16 índices viram tons de cinza do preto ao branco
+pub const GRAYSCALE: [u32; 16] = {
+    let mut palette = [0u32; 16];
+    let mut i = 0;
+    while i < 16 {
+        let level = (i * 17) as u32;
+        palette[i] = (level << 16) | (level << 8) | level;
+        i += 1;
+    }
+    palette
+};
+
+// Cor do GBA (5 bits por canal, vermelho nos bits baixos) em 0xRRGGBB, repetindo os bits altos nos baixos
+pub fn bgr555_to_rgb(color: u16) -> u32 {
+    let expand = |channel: u16| {
+        let value = (channel & 0x1F) as u32;
+        (value << 3) | (value >> 2)
+    };
+    (expand(color) << 16) | (expand(color >> 5) << 8) | expand(color >> 10)
+}
+
+// Paleta de 16 cores a partir de 32 bytes BGR555 little-endian
+pub fn palette(bytes: &[u8]) -> Option<[u32; 16]> {
+    let bytes = bytes.get(..32)?;
+    let mut palette = [0u32; 16];
+    for (color, pair) in palette.iter_mut().zip(bytes.chunks_exact(2)) {
+        *color = bgr555_to_rgb(u16::from_le_bytes([pair[0], pair[1]]));
+    }
+    Some(palette)
+}
+
+// Tamanho de cada pixel na tela para `tiles_wide` tiles ocuparem a largura da janela
+pub fn scale(tiles_wide: usize, width: usize) -> usize {
+    (width / (tiles_wide * 8)).max(1)
+}
+
+// Desenhar os tiles em um quadro de `width` x `height` pixels, da esquerda para a direita e de cima para baixo.
+// Em cada byte, o nibble baixo é o pixel da esquerda.
+pub fn render_4bpp(bytes: &[u8], tiles_wide: usize, palette: &[u32; 16], width: usize, height: usize) -> Vec<u32> {
+    let mut frame = vec![BACKGROUND; width * height];
+    let scale = scale(tiles_wide, width);
+    for (index, tile) in bytes.chunks_exact(TILE_BYTES).enumerate() {
+        let (tile_x, tile_y) = ((index % tiles_wide) * 8 * scale, (index / tiles_wide) * 8 * scale);
+        if tile_y >= height {
+            break;
+        }
+        for (i, &byte) in tile.iter().enumerate() {
+            for (half, nibble) in [byte & 0x0F, byte >> 4].into_iter().enumerate() {
+                let (x, y) = (tile_x + ((i % 4) * 2 + half) * scale, tile_y + (i / 4) * scale);
+                for row in y..(y + scale).min(height) {
+                    let start = row * width + x.min(width);
+                    let end = row * width + (x + scale).min(width);
+                    frame[start..end].fill(palette[nibble as usize]);
+                }
+            }
+        }
+    }
+    frame
+}
+
+// Amostras das cores em quadrados, `per_row` por linha, separados por 1 pixel do fundo
+pub fn render_swatches(colors: &[u32], per_row: usize, width: usize, height: usize) -> Vec<u32> {
+    let mut frame = vec![BACKGROUND; width * height];
+    let size = (width / per_row).max(2);
+    for (index, &color) in colors.iter().enumerate() {
+        let (x, y) = ((index % per_row) * size, (index / per_row) * size);
+        if y >= height {
+            break;
+        }
+        for row in y..(y + size - 1).min(height) {
+            let start = row * width + x.min(width);
+            let end = row * width + (x + size - 1).min(width);
+            frame[start..end].fill(color);
+        }
+    }
+    frame
+}
+
+// Índice da amostra sob um ponto da janela
+pub fn swatch_at(x: usize, y: usize, per_row: usize, width: usize) -> Option<usize> {
+    let size = (width / per_row).max(2);
+    (x / size < per_row).then(|| (y / size) * per_row + x / size)
+}
+
+// Contorno de 1 pixel de um quadrado de `size` pixels com o canto em (x, y), cortado nas bordas do quadro
+pub fn outline(frame: &mut [u32], width: usize, height: usize, (x, y): (usize, usize), size: usize, color: u32) {
+    let (right, bottom) = ((x + size).min(width), (y + size).min(height));
+    if x >= right || y >= bottom {
+        return;
+    }
+    for row in y..bottom {
+        if row == y || row == y + size - 1 {
+            frame[row * width + x..row * width + right].fill(color);
+        } else {
+            frame[row * width + x] = color;
+            if x + size - 1 < width {
+                frame[row * width + x + size - 1] = color;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_put_the_low_nibble_on_the_left() {
+        let mut tile = [0u8; TILE_BYTES];
+        tile[0] = 0x21;
+        tile[4] = 0xF0;
+        let frame = render_4bpp(&tile, 1, &GRAYSCALE, 8, 8);
+        assert_eq!(&frame[..3], &[GRAYSCALE[1], GRAYSCALE[2], GRAYSCALE[0]]);
+        assert_eq!(&frame[8..10], &[GRAYSCALE[0], GRAYSCALE[15]]);
+        assert_eq!(GRAYSCALE[15], 0xFFFFFF);
+    }
+
+    #[test]
+    fn tiles_scale_to_the_width_and_stop_at_the_bottom() {
+        assert_eq!((scale(2, 32), scale(4, 16)), (2, 1));
+        let mut tiles = vec![0u8; 3 * TILE_BYTES];
+        tiles[TILE_BYTES] = 0x0F;
+        tiles[2 * TILE_BYTES] = 0x0F;
+        // Dois tiles por linha com pixels de 2x2; o terceiro tile fica abaixo da janela
+        let frame = render_4bpp(&tiles, 2, &GRAYSCALE, 32, 16);
+        assert_eq!(&frame[16..19], &[0xFFFFFF, 0xFFFFFF, 0]);
+        assert_eq!(frame[32 + 17], 0xFFFFFF);
+        assert!(frame[..].iter().all(|&pixel| pixel != BACKGROUND));
+    }
+
+    #[test]
+    fn bgr555_colors_expand_to_eight_bits() {
+        assert_eq!(bgr555_to_rgb(0x001F), 0xFF0000);
+        assert_eq!(bgr555_to_rgb(0x03E0), 0x00FF00);
+        assert_eq!(bgr555_to_rgb(0x7C00), 0x0000FF);
+        assert_eq!(bgr555_to_rgb(0x8010), 0x840000);
+        let mut bytes = vec![0u8; 32];
+        bytes[2..4].copy_from_slice(&0x7FFFu16.to_le_bytes());
+        let colors = palette(&bytes).unwrap();
+        assert_eq!((colors[0], colors[1]), (0, 0xFFFFFF));
+        assert!(palette(&bytes[..31]).is_none());
+    }
+
+    #[test]
+    fn swatches_are_found_under_the_mouse() {
+        let frame = render_swatches(&[0x11, 0x22, 0x33], 2, 8, 8);
+        // Quadrados de 4 pixels com 1 de fundo entre eles
+        assert_eq!(&frame[..5], &[0x11, 0x11, 0x11, BACKGROUND, 0x22]);
+        assert_eq!(frame[4 * 8], 0x33);
+        assert_eq!(frame[3 * 8], BACKGROUND);
+        assert_eq!((swatch_at(5, 1, 2, 8), swatch_at(1, 5, 2, 8), swatch_at(8, 0, 2, 8)), (Some(1), Some(2), None));
+    }
+
+    #[test]
+    fn outlines_are_clipped_at_the_frame_edges() {
+        let mut frame = vec![0; 16];
+        outline(&mut frame, 4, 4, (0, 0), 3, CURSOR);
+        let c = CURSOR;
+        assert_eq!(frame, vec![c, c, c, 0, c, 0, c, 0, c, c, c, 0, 0, 0, 0, 0]);
+        // Passando da borda, só os lados visíveis aparecem
+        let mut frame = vec![0; 16];
+        outline(&mut frame, 4, 4, (2, 2), 3, CURSOR);
+        assert_eq!(frame, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, c, c, 0, 0, c, 0]);
+        outline(&mut frame, 4, 4, (4, 0), 3, 1);
+        assert!(!frame.contains(&1));
+    }
+}